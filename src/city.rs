@@ -1,13 +1,28 @@
 pub mod area;
+pub mod benchmark;
+pub mod careers;
+pub mod chronicle;
+pub mod config;
 pub mod culture;
 pub mod debug_timer;
+pub mod demand;
 pub mod dieties;
+pub mod epidemic;
+pub mod government;
+pub mod house;
+pub mod household;
 pub mod institutions;
+pub mod map;
+pub mod migration;
+pub mod parallel;
 pub mod population;
+pub mod site;
+pub mod snapshot;
+pub mod systems;
 pub mod city {
     use std::io::prelude::*;
     use std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeMap, BTreeSet},
         fmt,
         fs::File,
         io::LineWriter,
@@ -15,19 +30,37 @@ pub mod city {
     };
 
     use procgen_templater::dictionary::dictionary::Dictionary;
+    use rand::rngs::StdRng;
+    use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
+    use crate::city::chronicle::chronicle::{Chronicle, Event};
+    use crate::city::config::config::SimulationConfig;
     use crate::city::debug_timer::debub_timer::{new_debug_timer, DebugTimer};
-    use crate::city::population::mind::{mind::Mind, relations::relations::RelationVerb};
+    use crate::city::parallel::parallel::{phase_seed, propose_for_minds};
+    use crate::city::population::mind::{
+        mind::{CauseOfDeath, Mind},
+        relations::relations::RelationVerb,
+    };
+    use crate::city::systems::systems::{default_systems, SystemRegistry};
+    use crate::seeded::{
+        seeded::{random_id, seeded_rng},
+        templates::templates::Templates,
+    };
 
     use super::{
         area::area::{Area, AreaId},
-        culture::culture::{random_culture, Culture},
+        culture::culture::{random_culture, Culture, CultureDictionaries, CultureId},
+        epidemic::epidemic::Epidemic,
+        government::government::Government,
+        house::house::{House, HouseId},
+        household::household::{Household, HouseholdId},
         institutions::institutions::Institution,
         population::{mind::mind::random_mind, population::Population},
+        site::site::{random_city_name, random_site, CitySite},
     };
 
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub enum Era {
         Modern,
         Medieval,
@@ -44,81 +77,107 @@ pub mod city {
         }
     }
 
-    #[derive(PartialEq, Debug, Clone)]
+    impl Era {
+        // the tags words and templates are drawn from, fantasy borrows whatever is medieval
+        pub fn tags(self: &Self) -> Vec<String> {
+            return match self {
+                Era::Fantasy => vec![self.to_string(), Era::Medieval.to_string()],
+                _ => vec![self.to_string()],
+            };
+        }
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct City {
         pub id: Uuid,
         pub name: String,
+        pub seed: u64,
+        // templates belong to the data folder, like the dictionary, and are handed back on load
+        #[serde(skip)]
+        pub templates: Templates,
+        pub config: SimulationConfig,
+        pub systems: SystemRegistry,
+        #[serde(skip, default = "new_debug_timer")]
         pub debug_timer: DebugTimer,
+        pub site: CitySite,
         pub culture: Culture,
+        // cultures brought by immigrants, alongside the one the city was founded with
+        pub minorities: BTreeMap<CultureId, Culture>,
+        #[serde(skip)]
+        pub dictionaries: CultureDictionaries,
         pub population: Population,
-        pub areas: HashMap<AreaId, Area>,
-        pub institutions: HashMap<Uuid, Institution>,
+        pub areas: BTreeMap<AreaId, Area>,
+        pub households: BTreeMap<HouseholdId, Household>,
+        pub institutions: BTreeMap<Uuid, Institution>,
+        pub chronicle: Chronicle,
+        pub epidemics: Vec<Epidemic>,
+        pub government: Option<Government>,
+        pub houses: BTreeMap<HouseId, House>,
         pub year: usize,
     }
 
     impl City {
+        // each subsystem draws from its own stream, reseeded every year, so adding draws
+        // to one system doesn't shift the output of the others
+        pub fn rng(self: &Self, stream: &str) -> StdRng {
+            return seeded_rng(self.seed, stream, self.year);
+        }
         pub fn simulate_year(self: &mut Self, dict: &Dictionary) {
             self.year += 1;
             self.clear_timer();
-            self.increment_citizen_ages();
-            // employment
-            self.fire_percentage(0.05);
-            self.fill_and_create_jobs(dict);
-            //social
-            self.temp_add_friends();
-            self.update_mind_partner_relations();
-            self.generate_children(dict);
-
+            let names = self.culture_dictionary(dict);
+            for system in self.systems.ordered() {
+                let mut rng = self.rng(system.name());
+                self.debug_timer.start_system();
+                system.run(self, &names, &mut rng);
+                self.debug_timer.end_system(system.name());
+            }
             self.add_timestamp("remainder");
-            self.cleanup(5);
         }
-        fn increment_citizen_ages(self: &mut Self) {
-            let ref_citizens = self.population.clone();
-            for citizen in ref_citizens.values() {
-                if citizen.alive {
-                    let citizen_mut = self.population.get_mut(&citizen.id).unwrap();
-                    citizen_mut.age();
-                    if !citizen.alive {
-                        let spare: HashSet<Uuid> = HashSet::new();
-
-                        let partners = citizen
-                            .relations
-                            .get(&RelationVerb::Partner)
-                            .unwrap_or(&spare);
-                        for partner in partners {
-                            let m = self.population.get_mut(partner).unwrap();
-                            m.relations
-                                .get_mut(&RelationVerb::Partner)
-                                .unwrap()
-                                .remove(partner);
-                            if !m.relations.contains_key(&RelationVerb::LatePartner) {
-                                m.relations
-                                    .insert(RelationVerb::LatePartner, HashSet::new());
-                            }
-                            m.relations
-                                .get_mut(&RelationVerb::LatePartner)
-                                .unwrap()
-                                .insert(citizen.id.clone());
-                        }
-                        let spouses = citizen
-                            .relations
-                            .get(&RelationVerb::Spouse)
-                            .unwrap_or(&spare);
-                        for spouse in spouses {
-                            let m = self.population.get_mut(spouse).unwrap();
-                            m.relations
-                                .get_mut(&RelationVerb::Spouse)
-                                .unwrap()
-                                .remove(spouse);
-                            if !m.relations.contains_key(&RelationVerb::LateSpouse) {
-                                m.relations.insert(RelationVerb::LateSpouse, HashSet::new());
-                            }
-                            m.relations
-                                .get_mut(&RelationVerb::LateSpouse)
-                                .unwrap()
-                                .insert(citizen.id.clone());
-                        }
+        pub fn increment_citizen_ages(self: &mut Self, rng: &mut StdRng) {
+            let mortality = &self.config.mortality;
+            let health = &self.config.health;
+            let (culture, minorities) = (&self.culture, &self.minorities);
+            let population = &self.population;
+            let fates = propose_for_minds(&self.current_citizens(), phase_seed(rng), |id, rng| {
+                let mind = population.get(id).unwrap();
+                let culture = minorities.get(&mind.culture).unwrap_or(culture);
+                return (
+                    id.clone(),
+                    mind.dies_this_year(mortality, health, culture, rng),
+                );
+            });
+            for (id, cause) in fates {
+                self.population.get_mut(&id).unwrap().age();
+                if cause.is_some() {
+                    self.kill_mind(&id, cause.unwrap());
+                }
+            }
+        }
+        pub fn kill_mind(self: &mut Self, id: &Uuid, cause: CauseOfDeath) {
+            self.population.get_mut(id).unwrap().die(cause);
+            self.end_role(id);
+            self.vacate_mind(id);
+            let year = self.year;
+            self.record_event(Event::Death {
+                year,
+                mind: id.clone(),
+            });
+            // the partners and spouses left behind are widowed
+            for (verb, late_verb) in [
+                (RelationVerb::Partner, RelationVerb::LatePartner),
+                (RelationVerb::Spouse, RelationVerb::LateSpouse),
+            ] {
+                let partners = self.population.get(id).unwrap().get_relations(verb.clone());
+                for partner in partners {
+                    let m = self.population.get_mut(&partner).unwrap();
+                    if m.relations.contains_key(&verb) {
+                        m.relations.get_mut(&verb).unwrap().remove(id);
+                    }
+                    if !m.relations.contains_key(&late_verb) {
+                        m.relations.insert(late_verb.clone(), BTreeSet::new());
                     }
+                    m.relations.get_mut(&late_verb).unwrap().insert(id.clone());
                 }
             }
         }
@@ -150,6 +209,20 @@ pub mod city {
                 living_citizens.len(),
                 (single_citizens.len() as f32 / living_citizens.len() as f32) * 100.0
             );
+            let (arrived, left) = self.migration_in_year(self.year);
+            println!("Migration: {} arrived, {} left", arrived, left);
+            if self.minorities.len() > 0 {
+                let counts: Vec<String> = self
+                    .culture_counts()
+                    .iter()
+                    .map(|(culture, count)| format!("{} {}", culture.homeland, count))
+                    .collect();
+                println!("Cultures: {}", counts.join(", "));
+            }
+            if self.government.is_some() && self.government.as_ref().unwrap().ruler.is_some() {
+                let ruler = self.government.as_ref().unwrap().ruler.unwrap();
+                println!("Ruler: {}", self.titled_name(&ruler));
+            }
         }
         pub fn population_graph(self: &Self) {
             let bucket_size: usize = 10;
@@ -192,25 +265,82 @@ pub mod city {
             println!(" {}", axis_line);
         }
 
-        pub fn export(self: &Self) {
-            let mind_file = File::create(Path::new("./export/minds_export.md")).unwrap();
-            let institution_file =
-                File::create(Path::new("./export/institutions_export.md")).unwrap();
-            let mut mind_file_writer = LineWriter::new(mind_file);
-            let mut institution_file_writer = LineWriter::new(institution_file);
-
+        pub fn export_minds(self: &Self) -> String {
+            let mut output = String::new();
             for id in self.current_citizens() {
-                mind_file_writer
-                    .write_all(self.population.get(&id).unwrap().print(&self).as_bytes())
-                    .unwrap();
+                output += &self.population.get(&id).unwrap().print(&self);
             }
-            for id in self.institutions.keys() {
-                institution_file_writer
-                    .write_all(self.institutions.get(&id).unwrap().print(&self).as_bytes())
-                    .unwrap();
+            let dead: Vec<&Mind> = self
+                .population
+                .values()
+                .filter(|m| !m.alive && m.emigrated.is_none())
+                .collect();
+            if dead.len() > 0 {
+                output += "## Deceased  \n";
+                for mind in dead {
+                    output += &mind.print(&self);
+                }
+            }
+            let emigrants: Vec<&Mind> = self
+                .population
+                .values()
+                .filter(|m| m.emigrated.is_some())
+                .collect();
+            if emigrants.len() > 0 {
+                output += "## Emigrated  \n";
+                for mind in emigrants {
+                    output += &mind.print(&self);
+                }
+            }
+            return output;
+        }
+
+        pub fn export_institutions(self: &Self) -> String {
+            let mut output = String::new();
+            for institution in self.institutions.values() {
+                output += &institution.print(&self);
+            }
+            return output;
+        }
+
+        pub fn export(self: &Self, output_dir: &str) -> Result<(), String> {
+            let dir = Path::new(output_dir);
+            if !dir.is_dir() {
+                return Err(format!("Output folder {} does not exist", output_dir));
+            }
+            for (file_name, title, contents) in [
+                ("minds_export.md", "Citizens", self.export_minds()),
+                (
+                    "institutions_export.md",
+                    "Institutions",
+                    self.export_institutions(),
+                ),
+                ("areas_export.md", "Districts", self.export_areas()),
+                (
+                    "households_export.md",
+                    "Households",
+                    self.export_households(),
+                ),
+                ("chronicle_export.md", "Chronicle", self.export_chronicle()),
+                ("demand_export.md", "Demand", self.export_demand()),
+                (
+                    "government_export.md",
+                    "Government",
+                    self.export_government(),
+                ),
+                ("houses_export.md", "House Roll", self.export_houses()),
+            ] {
+                let contents = format!("# {}: {}\n{}", self.name, title, contents);
+                let path = dir.join(file_name);
+                let file = File::create(&path)
+                    .map_err(|e| format!("Could not create {}: {}", path.display(), e))?;
+                let mut writer = LineWriter::new(file);
+                writer
+                    .write_all(contents.as_bytes())
+                    .and_then(|_| writer.flush())
+                    .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
             }
-            mind_file_writer.flush().unwrap();
-            institution_file_writer.flush().unwrap();
+            return Ok(());
         }
         pub fn cleanup(self: &mut Self, interval: usize) {
             let rem = self.year.checked_rem(interval);
@@ -226,12 +356,20 @@ pub mod city {
                         mind.employer = None;
                     }
                 }
-                let institutions_clone = self.institutions.clone();
-                let institutions = institutions_clone.values();
-                for inst in institutions {
-                    if inst.staff.len().eq(&0) {
-                        self.institutions.remove(&inst.id);
-                    }
+                let empty: Vec<Uuid> = self
+                    .institutions
+                    .values()
+                    .filter(|i| i.staff.len().eq(&0))
+                    .map(|i| i.id.clone())
+                    .collect();
+                for id in empty {
+                    self.vacate_institution(&id);
+                    let inst = self.institutions.remove(&id).unwrap();
+                    self.record_event(Event::InstitutionClosed {
+                        year: self.year,
+                        institution: inst.id,
+                        name: inst.name,
+                    });
                 }
             }
         }
@@ -249,22 +387,124 @@ pub mod city {
         }
     }
 
-    pub fn random_city(dict: &Dictionary, era: Era, base_population: usize) -> City {
-        let culture = random_culture(dict, era);
-        let mut population: Population = HashMap::new();
+    pub fn random_city(
+        dict: &Dictionary,
+        templates: &Templates,
+        config: &SimulationConfig,
+        era: Era,
+        base_population: usize,
+        seed: u64,
+    ) -> City {
+        let mut rng = seeded_rng(seed, "founding", 0);
+        let site = random_site(dict, &mut seeded_rng(seed, "site", 0));
+        let mut culture = random_culture(
+            dict,
+            templates,
+            &site,
+            era,
+            &mut seeded_rng(seed, "culture", 0),
+        );
+        let mut dictionaries = CultureDictionaries::new(dict);
+        dictionaries.add(&culture);
+        let names = dictionaries.get(&culture.id).unwrap();
+        let name = random_city_name(
+            &names,
+            templates,
+            &site,
+            &culture,
+            &mut seeded_rng(seed, "name", 0),
+        );
+        culture.homeland = name.clone();
+        let mut population: Population = BTreeMap::new();
+        let mut population_rng = seeded_rng(seed, "population", 0);
         for _i in 0..base_population {
-            let m = random_mind(&dict, &culture, 0);
+            let m = random_mind(
+                &names,
+                &culture,
+                &config.demographics,
+                0,
+                &mut population_rng,
+            );
             population.insert(m.id.clone(), m);
         }
-        return City {
-            id: Uuid::new_v4(),
-            name: String::new(),
+        let mut city = City {
+            id: random_id(&mut rng),
+            name,
+            seed,
+            templates: templates.clone(),
+            config: config.clone(),
+            systems: default_systems(),
             debug_timer: new_debug_timer(),
+            site,
             culture,
+            minorities: BTreeMap::new(),
+            dictionaries,
             population,
-            areas: HashMap::new(),
-            institutions: HashMap::new(),
+            areas: BTreeMap::new(),
+            households: BTreeMap::new(),
+            institutions: BTreeMap::new(),
+            chronicle: Chronicle::new(),
+            epidemics: Vec::new(),
+            government: None,
+            houses: BTreeMap::new(),
             year: 0,
         };
+        city.found_areas(&names, &mut seeded_rng(seed, "areas", 0));
+        city.update_households(&names, &mut seeded_rng(seed, "households", 0));
+        return city;
+    }
+
+    #[test]
+    fn test_seeded_city_is_reproducible() {
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let run = |seed: u64| {
+            let mut city = random_city(
+                &dict,
+                &templates,
+                &SimulationConfig::default(),
+                Era::Medieval,
+                40,
+                seed,
+            );
+            for _i in 0..15 {
+                city.simulate_year(&dict);
+            }
+            return format!("{}{}", city.export_minds(), city.export_institutions());
+        };
+        assert_eq!(run(143), run(143));
+        assert_ne!(run(143), run(144));
+    }
+
+    #[test]
+    fn test_names_follow_the_culture() {
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            200,
+            17,
+        );
+        let historical: BTreeSet<String> = city
+            .culture
+            .historical_names
+            .iter()
+            .flat_map(|(_, first, last)| [first.base.clone(), last.base.clone()])
+            .collect();
+        let named_for_history = city
+            .population
+            .values()
+            .filter(|m| historical.contains(&m.first_name) || historical.contains(&m.last_name))
+            .count();
+        // the historical figures' names make up a small share of the raw dictionary
+        assert!(named_for_history * 2 > city.population.len());
+        assert!(city.dictionary_for(&dict, &city.culture.id).words.len() > dict.words.len());
     }
 }
@@ -1,33 +1,251 @@
 pub mod area {
-    use crate::city::city::City;
+    use std::collections::BTreeSet;
+
+    use crate::{
+        city::{
+            chronicle::chronicle::Event, city::City, config::config::AreaConfig,
+            institutions::institutions::InstitutionId, population::mind::mind::MindId,
+            site::site::CitySite,
+        },
+        seeded::{seeded::random_id, templates::templates::Templates},
+    };
     use procgen_templater::dictionary::dictionary::Dictionary;
-    use rand::Rng;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+    use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
     pub type AreaId = Uuid;
 
-    #[derive(PartialEq, Debug, Clone)]
+    // names are rerolled this many times when a new district would share one with an old one
+    const AREA_NAME_ATTEMPTS: usize = 5;
+
+    // size is how many residents the area can house, each institution takes up
+    // institution_footprint of that
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct Area {
         pub id: AreaId,
         pub name: String,
         pub size: usize,
+        pub founded_year: usize,
+        pub residents: BTreeSet<MindId>,
+        pub institutions: BTreeSet<InstitutionId>,
+    }
+
+    impl Area {
+        pub fn occupied(self: &Self, config: &AreaConfig) -> usize {
+            return self.residents.len() + self.institutions.len() * config.institution_footprint;
+        }
+
+        pub fn print(self: &Self, city: &City) -> String {
+            let mut output = String::new();
+            output += &format!("### {}  \n", self.name);
+            output += &format!(
+                "Occupancy: {}/{}  \n",
+                self.occupied(&city.config.areas),
+                self.size
+            );
+            output += &format!("Residents: {}  \n", self.residents.len());
+            if self.institutions.len() > 0 {
+                output += "Institutions:  \n";
+                for i_id in &self.institutions {
+                    output += &format!("    {}  \n", city.institution_name(i_id));
+                }
+            }
+            return output;
+        }
     }
 
     impl City {
-        pub fn area_is_full(_area_id: AreaId) -> bool {
-            return false;
+        pub fn area_is_full(self: &Self, area_id: &AreaId) -> bool {
+            let area = self.areas.get(area_id);
+            return area.is_none()
+                || area.unwrap().occupied(&self.config.areas) >= area.unwrap().size;
+        }
+
+        fn area_has_room_for(self: &Self, area_id: &AreaId, space: usize) -> bool {
+            let area = self.areas.get(area_id).unwrap();
+            return area.occupied(&self.config.areas) + space <= area.size;
+        }
+
+        // a random district with space left, founding a new one when every district is full
+        pub fn find_area_with_room(
+            self: &mut Self,
+            dict: &Dictionary,
+            space: usize,
+            rng: &mut StdRng,
+        ) -> AreaId {
+            let mut with_room: Vec<AreaId> = self
+                .areas
+                .keys()
+                .filter(|id| self.area_has_room_for(id, space))
+                .cloned()
+                .collect();
+            with_room.shuffle(rng);
+            if with_room.len() > 0 {
+                return with_room.first().unwrap().clone();
+            }
+
+            let site_dict = self.site.dictionary(dict);
+            let mut area = random_area(
+                &site_dict,
+                &self.templates,
+                &self.config.areas,
+                &self.site,
+                rng,
+            );
+            for _attempt in 0..AREA_NAME_ATTEMPTS {
+                if !self.areas.values().any(|a| a.name.eq(&area.name)) {
+                    break;
+                }
+                area = random_area(
+                    &site_dict,
+                    &self.templates,
+                    &self.config.areas,
+                    &self.site,
+                    rng,
+                );
+            }
+            area.size = area.size.max(space);
+            area.founded_year = self.year;
+            let id = area.id.clone();
+            if self.year > 0 {
+                self.record_event(Event::AreaFounded {
+                    year: self.year,
+                    area: id.clone(),
+                    name: area.name.clone(),
+                });
+            }
+            self.areas.insert(id.clone(), area);
+            return id;
+        }
+
+        // lays out enough districts for the founding population, then settles it into them
+        pub fn found_areas(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            let founders = self.current_citizens();
+            let needed =
+                (founders.len() as f32 * (1.0 + self.config.areas.founding_headroom)) as usize;
+            let mut capacity: usize = self.areas.values().map(|a| a.size).sum();
+            let site_dict = self.site.dictionary(dict);
+            while capacity < needed.max(1) {
+                let area = random_area(
+                    &site_dict,
+                    &self.templates,
+                    &self.config.areas,
+                    &self.site,
+                    rng,
+                );
+                capacity += area.size;
+                self.areas.insert(area.id.clone(), area);
+            }
+            for id in founders {
+                let area_id = self.find_area_with_room(dict, 1, rng);
+                self.move_mind_to_area(&id, &area_id);
+            }
+        }
+
+        pub fn move_mind_to_area(self: &mut Self, mind_id: &MindId, area_id: &AreaId) {
+            let mind = self.population.get_mut(mind_id).unwrap();
+            if mind.area.is_some() {
+                let old_area = self.areas.get_mut(&mind.area.unwrap());
+                if old_area.is_some() {
+                    old_area.unwrap().residents.remove(mind_id);
+                }
+            }
+            mind.area = Some(area_id.clone());
+            self.areas
+                .get_mut(area_id)
+                .unwrap()
+                .residents
+                .insert(mind_id.clone());
+        }
+
+        // the district a new citizen settles in, next to the given minds if there is space
+        pub fn settle_mind(
+            self: &mut Self,
+            dict: &Dictionary,
+            mind_id: &MindId,
+            near: Vec<MindId>,
+            rng: &mut StdRng,
+        ) {
+            let nearby = near
+                .iter()
+                .filter_map(|id| self.population.get(id).and_then(|m| m.area))
+                .find(|area_id| !self.area_is_full(area_id));
+            let area_id = if nearby.is_some() {
+                nearby.unwrap()
+            } else {
+                self.find_area_with_room(dict, 1, rng)
+            };
+            self.move_mind_to_area(mind_id, &area_id);
+        }
+
+        pub fn settle_institution(
+            self: &mut Self,
+            dict: &Dictionary,
+            institution_id: &InstitutionId,
+            rng: &mut StdRng,
+        ) {
+            let footprint = self.config.areas.institution_footprint;
+            let area_id = self.find_area_with_room(dict, footprint, rng);
+            self.institutions.get_mut(institution_id).unwrap().area = Some(area_id.clone());
+            self.areas
+                .get_mut(&area_id)
+                .unwrap()
+                .institutions
+                .insert(institution_id.clone());
+        }
+
+        // the dead and the closed stop taking up space, but keep the area they were last in
+        pub fn vacate_mind(self: &mut Self, mind_id: &MindId) {
+            let area_id = self.population.get(mind_id).unwrap().area;
+            if area_id.is_some() && self.areas.contains_key(&area_id.unwrap()) {
+                self.areas
+                    .get_mut(&area_id.unwrap())
+                    .unwrap()
+                    .residents
+                    .remove(mind_id);
+            }
+        }
+
+        pub fn vacate_institution(self: &mut Self, institution_id: &InstitutionId) {
+            for area in self.areas.values_mut() {
+                area.institutions.remove(institution_id);
+            }
+        }
+
+        pub fn area_name(self: &Self, area_id: &AreaId) -> String {
+            let area = self.areas.get(area_id);
+            return if area.is_some() {
+                area.unwrap().name.clone()
+            } else {
+                String::from("a forgotten district")
+            };
+        }
+
+        pub fn export_areas(self: &Self) -> String {
+            let mut output = String::new();
+            for area in self.areas.values() {
+                output += &area.print(&self);
+            }
+            return output;
         }
     }
 
-    pub fn random_area(dict: &Dictionary) -> Area {
-        let name_template = dict
-            .get_random_template(vec![vec!["AreaName".to_string()]])
-            .unwrap();
-        let mut rand = rand::thread_rng();
+    // named from the site's dictionary, built by the caller once for all the areas it founds
+    pub fn random_area(
+        site_dict: &Dictionary,
+        templates: &Templates,
+        config: &AreaConfig,
+        site: &CitySite,
+        rng: &mut StdRng,
+    ) -> Area {
         return Area {
-            id: Uuid::new_v4() as AreaId,
-            name: dict.render_template(&name_template.id).unwrap(),
-            size: (rand.gen::<f32>() * 20.0) as usize,
+            id: random_id(rng) as AreaId,
+            name: site.random_place_name(site_dict, templates, rng),
+            size: rng.gen_range(config.min_size..=config.max_size.max(config.min_size)),
+            founded_year: 0,
+            residents: BTreeSet::new(),
+            institutions: BTreeSet::new(),
         };
     }
 
@@ -36,16 +254,65 @@ pub mod area {
         use crate::city::{
             city::Era,
             culture::culture::{random_culture, rebalance_dict_for_culture},
+            site::site::random_site,
+        };
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
         };
         use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
         let dict = build_dictionary_from_folder("./data_files");
-        let culture = random_culture(&dict, Era::Medieval);
+        let templates = build_templates_from_folder("./data_files");
+        let mut rng = seeded_rng(0, "test", 0);
+        let site = random_site(&dict, &mut rng);
+        let culture = random_culture(&dict, &templates, &site, Era::Medieval, &mut rng);
         let dict2 = rebalance_dict_for_culture(&culture, &dict);
         for _i in 0..100 {
-            let t = dict2
-                .get_random_template(vec![vec!["AreaName".to_string()]])
+            let t = templates
+                .get_random_template(vec![vec!["AreaName".to_string()]], &mut rng)
                 .unwrap();
-            println!("{}", dict2.render_template_as_title(&t.id).unwrap());
+            println!("{}", t.render_as_title(&dict2, &mut rng).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_areas_house_the_population() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            200,
+            11,
+        );
+        let founding_areas = city.areas.len();
+        assert!(founding_areas > 0);
+        for _i in 0..40 {
+            city.simulate_year(&dict);
+        }
+        assert!(city.areas.len() > founding_areas);
+        for area in city.areas.values() {
+            assert!(area.occupied(&city.config.areas) <= area.size);
+        }
+        for id in city.current_citizens() {
+            let area = city.population.get(&id).unwrap().area.unwrap();
+            assert!(city.areas.get(&area).unwrap().residents.contains(&id));
+        }
+        for institution in city.institutions.values() {
+            let area = institution.area.unwrap();
+            assert!(city
+                .areas
+                .get(&area)
+                .unwrap()
+                .institutions
+                .contains(&institution.id));
         }
     }
 }
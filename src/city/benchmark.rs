@@ -0,0 +1,105 @@
+pub mod benchmark {
+    use std::time::{Duration, Instant};
+
+    use procgen_templater::dictionary::dictionary::Dictionary;
+
+    use crate::city::city::City;
+
+    pub struct BenchmarkReport {
+        pub years: usize,
+        pub starting_population: usize,
+        pub final_population: usize,
+        pub total: Duration,
+        // summed over every simulated year, in the order the systems first ran
+        pub system_times: Vec<(String, Duration)>,
+    }
+
+    impl BenchmarkReport {
+        pub fn print(self: &Self) {
+            println!(
+                "Simulated {} years, {} -> {} citizens in {:.2}s ({:.1}ms/year)",
+                self.years,
+                self.starting_population,
+                self.final_population,
+                self.total.as_secs_f32(),
+                self.total.as_secs_f32() * 1000.0 / self.years.max(1) as f32
+            );
+            for (name, time) in &self.system_times {
+                println!(
+                    "  {:<30} {:>10.1}ms {:>6.1}%",
+                    format!("{name}:"),
+                    time.as_secs_f32() * 1000.0,
+                    time.as_secs_f32() / self.total.as_secs_f32().max(f32::EPSILON) * 100.0
+                );
+            }
+        }
+    }
+
+    impl City {
+        pub fn benchmark(self: &mut Self, dict: &Dictionary, years: usize) -> BenchmarkReport {
+            let starting_population = self.current_citizens().len();
+            let mut system_times: Vec<(String, Duration)> = Vec::new();
+            let start = Instant::now();
+            for _i in 0..years {
+                self.simulate_year(dict);
+                for (name, time) in &self.debug_timer.system_times {
+                    let matching = system_times.iter_mut().find(|(n, _t)| n.eq(name));
+                    if matching.is_some() {
+                        matching.unwrap().1 += *time;
+                    } else {
+                        system_times.push((name.clone(), *time));
+                    }
+                }
+            }
+            return BenchmarkReport {
+                years,
+                starting_population,
+                final_population: self.current_citizens().len(),
+                total: start.elapsed(),
+                system_times,
+            };
+        }
+    }
+
+    #[test]
+    fn test_benchmark_times_every_system() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            30,
+            0,
+        );
+        city.systems.set_enabled("cleanup", false);
+        let report = city.benchmark(&dict, 5);
+        let timed: Vec<&String> = report.system_times.iter().map(|(n, _t)| n).collect();
+        assert_eq!(
+            timed,
+            vec![
+                "health",
+                "epidemics",
+                "ageing",
+                "firing",
+                "promotions",
+                "hiring",
+                "friends",
+                "partners",
+                "children",
+                "migration",
+                "households",
+                "government",
+                "houses"
+            ]
+        );
+        assert_eq!(report.years, 5);
+    }
+}
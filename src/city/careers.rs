@@ -0,0 +1,310 @@
+pub mod careers {
+    use rand::{rngs::StdRng, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use crate::city::{
+        chronicle::chronicle::Event,
+        city::City,
+        institutions::institutions::{Institution, InstitutionId, StaffDefinition},
+        population::mind::mind::MindId,
+    };
+
+    // a post a mind held, still held while it hasn't ended
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Role {
+        pub institution: InstitutionId,
+        pub title: String,
+        pub began: usize,
+        pub ended: Option<usize>,
+    }
+
+    impl Institution {
+        pub fn is_manager(self: &Self, title: &String) -> bool {
+            return self.management.iter().any(|m| m.title.eq(title));
+        }
+
+        // a manager's post the institution is hiring for, left empty or grown into
+        pub fn vacant_manager(self: &Self) -> Option<String> {
+            return self.next_role().filter(|title| self.is_manager(title));
+        }
+
+        fn reports_to(self: &Self, manager: &String, title: &String) -> bool {
+            return self
+                .management
+                .iter()
+                .any(|m| m.title.eq(manager) && m.reportee_types.contains(title));
+        }
+    }
+
+    impl City {
+        pub fn begin_role(
+            self: &mut Self,
+            mind_id: &MindId,
+            institution: &InstitutionId,
+            title: &String,
+        ) {
+            self.end_role(mind_id);
+            let year = self.year;
+            self.population.get_mut(mind_id).unwrap().career.push(Role {
+                institution: institution.clone(),
+                title: title.clone(),
+                began: year,
+                ended: None,
+            });
+        }
+
+        pub fn end_role(self: &mut Self, mind_id: &MindId) {
+            let year = self.year;
+            let role = self.population.get_mut(mind_id).unwrap().career.last_mut();
+            if role.is_some() && role.as_ref().unwrap().ended.is_none() {
+                role.unwrap().ended = Some(year);
+            }
+        }
+
+        // years of service weighed by how conscientious they are
+        fn promotion_score(self: &Self, mind_id: &MindId, started_year: usize) -> f32 {
+            let conscientiousness = self
+                .population
+                .get(mind_id)
+                .unwrap()
+                .personality
+                .matrix
+                .conscientiousness;
+            return (self.year - started_year) as f32 * (0.5 + conscientiousness);
+        }
+
+        // the best candidate for a manager's post among the staff who have served long enough,
+        // from the institution itself or, when elsewhere, from one that can spare them
+        fn best_candidate(
+            self: &Self,
+            institution_id: &InstitutionId,
+            title: &String,
+            internal: bool,
+        ) -> Option<(InstitutionId, MindId, f32)> {
+            let service = self.config.employment.promotion_service;
+            let manager_post = self.institutions.get(institution_id).unwrap();
+            return self
+                .institutions
+                .values()
+                .filter(|i| {
+                    if internal {
+                        return i.id.eq(institution_id);
+                    }
+                    return !i.id.eq(institution_id) && i.staff.len() > 1;
+                })
+                .flat_map(|i| {
+                    i.staff
+                        .values()
+                        .filter(move |s| {
+                            if internal {
+                                return manager_post.reports_to(title, &s.title);
+                            }
+                            return !i.is_manager(&s.title);
+                        })
+                        .map(move |s| (i.id.clone(), s))
+                })
+                .filter(|(_, s)| self.year - s.started_year >= service)
+                .map(|(from, s)| {
+                    (
+                        from,
+                        s.employee_id.clone(),
+                        self.promotion_score(&s.employee_id, s.started_year),
+                    )
+                })
+                .fold(None, |best: Option<(InstitutionId, MindId, f32)>, c| {
+                    if best.is_none() || c.2 > best.as_ref().unwrap().2 {
+                        return Some(c);
+                    }
+                    return best;
+                });
+        }
+
+        // Posts left by staff who have died are vacated first. Staff who have served
+        // promotion_service years are candidates for the manager's posts institutions are
+        // hiring for. A better candidate working elsewhere takes the post at
+        // job_change_chance, otherwise the best of the institution's own staff is promoted,
+        // and posts no one can fill are left to hiring.
+        pub fn promote_staff(self: &mut Self, rng: &mut StdRng) {
+            let population = &self.population;
+            for institution in self.institutions.values_mut() {
+                institution
+                    .staff
+                    .retain(|id, _| population.get(id).unwrap().alive);
+            }
+            let institution_ids: Vec<InstitutionId> = self.institutions.keys().cloned().collect();
+            for institution_id in &institution_ids {
+                loop {
+                    let title = self
+                        .institutions
+                        .get(institution_id)
+                        .unwrap()
+                        .vacant_manager();
+                    if title.is_none() {
+                        break;
+                    }
+                    let title = title.unwrap();
+                    let internal = self.best_candidate(institution_id, &title, true);
+                    let external = self.best_candidate(institution_id, &title, false);
+                    if external.is_some()
+                        && (internal.is_none()
+                            || external.as_ref().unwrap().2 > internal.as_ref().unwrap().2)
+                        && rng.gen::<f32>() < self.config.employment.job_change_chance
+                    {
+                        let (from, mind_id, _) = external.unwrap();
+                        self.change_employer(&mind_id, &from, institution_id, &title);
+                    } else if internal.is_some() {
+                        let (_, mind_id, _) = internal.unwrap();
+                        let year = self.year;
+                        let staff = self
+                            .institutions
+                            .get_mut(institution_id)
+                            .unwrap()
+                            .staff
+                            .get_mut(&mind_id)
+                            .unwrap();
+                        staff.title = title.clone();
+                        staff.started_year = year;
+                        self.begin_role(&mind_id, institution_id, &title);
+                        self.record_event(Event::Promoted {
+                            year: self.year,
+                            mind: mind_id,
+                            institution: institution_id.clone(),
+                            title,
+                        });
+                    } else {
+                        break;
+                    }
+                }
+            }
+            self.add_timestamp("promote_staff");
+        }
+
+        fn change_employer(
+            self: &mut Self,
+            mind_id: &MindId,
+            from: &InstitutionId,
+            institution_id: &InstitutionId,
+            title: &String,
+        ) {
+            self.institutions
+                .get_mut(from)
+                .unwrap()
+                .staff
+                .remove(mind_id);
+            self.institutions
+                .get_mut(institution_id)
+                .unwrap()
+                .staff
+                .insert(
+                    mind_id.clone(),
+                    StaffDefinition {
+                        title: title.clone(),
+                        employee_id: mind_id.clone(),
+                        salary: 0,
+                        started_year: self.year,
+                    },
+                );
+            self.population.get_mut(mind_id).unwrap().employer = Some(institution_id.clone());
+            self.begin_role(mind_id, institution_id, title);
+            self.record_event(Event::ChangedEmployer {
+                year: self.year,
+                mind: mind_id.clone(),
+                from: from.clone(),
+                institution: institution_id.clone(),
+                title: title.clone(),
+            });
+        }
+
+        pub fn describe_career(self: &Self, mind_id: &MindId) -> Vec<String> {
+            return self
+                .population
+                .get(mind_id)
+                .unwrap()
+                .career
+                .iter()
+                .map(|role| {
+                    format!(
+                        "{} at {}, {} to {}",
+                        role.title,
+                        self.institution_name(&role.institution),
+                        role.began,
+                        if role.ended.is_some() {
+                            role.ended.unwrap().to_string()
+                        } else {
+                            String::from("now")
+                        }
+                    )
+                })
+                .collect();
+        }
+    }
+
+    #[test]
+    fn test_staff_are_promoted() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let config = SimulationConfig::default();
+        let mut city = random_city(&dict, &templates, &config, Era::Medieval, 120, 21);
+        for _i in 0..30 {
+            city.simulate_year(&dict);
+        }
+        let promotions: Vec<(usize, MindId, InstitutionId, String)> = city
+            .chronicle
+            .events()
+            .iter()
+            .filter_map(|e| match e {
+                Event::Promoted {
+                    year,
+                    mind,
+                    institution,
+                    title,
+                } => Some((*year, mind.clone(), institution.clone(), title.clone())),
+                _ => None,
+            })
+            .collect();
+        assert!(promotions.len() > 0);
+        // a new post has to be served in before the next, so no one rises twice in a year
+        let mut promoted_in_year: Vec<(usize, MindId)> =
+            promotions.iter().map(|p| (p.0, p.1.clone())).collect();
+        promoted_in_year.sort();
+        promoted_in_year.dedup();
+        assert_eq!(promoted_in_year.len(), promotions.len());
+        for (year, mind, institution, title) in promotions {
+            let career = &city.population.get(&mind).unwrap().career;
+            let promoted = career
+                .iter()
+                .position(|r| {
+                    r.began == year && r.institution.eq(&institution) && r.title.eq(&title)
+                })
+                .unwrap();
+            // promoted from a post they had served long enough in
+            let previous = &career[promoted - 1];
+            assert!(previous.institution.eq(&institution));
+            assert!(year - previous.began >= config.employment.promotion_service);
+        }
+        // everyone in work holds the post their career ends with, served since it began
+        for mind in city
+            .population
+            .values()
+            .filter(|m| m.alive && m.employer.is_some())
+        {
+            let role = mind.career.last().unwrap();
+            let staff = city
+                .institutions
+                .get(&mind.employer.unwrap())
+                .unwrap()
+                .staff
+                .get(&mind.id);
+            assert!(role.ended.is_none());
+            assert!(role.institution.eq(&mind.employer.unwrap()));
+            assert_eq!(role.title, staff.unwrap().title);
+            assert_eq!(role.began, staff.unwrap().started_year);
+        }
+    }
+}
@@ -0,0 +1,503 @@
+pub mod chronicle {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        city::{
+            area::area::AreaId, city::City, house::house::HouseId,
+            institutions::institutions::InstitutionId, population::mind::mind::MindId,
+        },
+        grammar::grammar::render_list,
+    };
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub enum Event {
+        Birth {
+            year: usize,
+            child: MindId,
+            parents: Vec<MindId>,
+        },
+        Death {
+            year: usize,
+            mind: MindId,
+        },
+        Partnered {
+            year: usize,
+            minds: [MindId; 2],
+        },
+        Married {
+            year: usize,
+            minds: [MindId; 2],
+        },
+        Separated {
+            year: usize,
+            minds: [MindId; 2],
+        },
+        Divorced {
+            year: usize,
+            minds: [MindId; 2],
+        },
+        Hired {
+            year: usize,
+            mind: MindId,
+            institution: InstitutionId,
+            title: String,
+        },
+        Fired {
+            year: usize,
+            mind: MindId,
+            institution: InstitutionId,
+        },
+        Promoted {
+            year: usize,
+            mind: MindId,
+            institution: InstitutionId,
+            title: String,
+        },
+        ChangedEmployer {
+            year: usize,
+            mind: MindId,
+            from: InstitutionId,
+            institution: InstitutionId,
+            title: String,
+        },
+        InstitutionFounded {
+            year: usize,
+            institution: InstitutionId,
+            name: String,
+        },
+        InstitutionClosed {
+            year: usize,
+            institution: InstitutionId,
+            name: String,
+        },
+        AreaFounded {
+            year: usize,
+            area: AreaId,
+            name: String,
+        },
+        OutbreakBegan {
+            year: usize,
+            disease: String,
+            cases: Vec<MindId>,
+        },
+        OutbreakEnded {
+            year: usize,
+            disease: String,
+            cases: usize,
+            deaths: usize,
+        },
+        Immigrated {
+            year: usize,
+            minds: Vec<MindId>,
+            origin: String,
+        },
+        Emigrated {
+            year: usize,
+            minds: Vec<MindId>,
+        },
+        Succession {
+            year: usize,
+            ruler: MindId,
+            predecessor: Option<MindId>,
+            title: String,
+        },
+        HouseFounded {
+            year: usize,
+            house: HouseId,
+            name: String,
+            founder: MindId,
+        },
+    }
+
+    impl Event {
+        pub fn year(self: &Self) -> usize {
+            return match self {
+                Event::Birth { year, .. }
+                | Event::Death { year, .. }
+                | Event::Partnered { year, .. }
+                | Event::Married { year, .. }
+                | Event::Separated { year, .. }
+                | Event::Divorced { year, .. }
+                | Event::Hired { year, .. }
+                | Event::Fired { year, .. }
+                | Event::Promoted { year, .. }
+                | Event::ChangedEmployer { year, .. }
+                | Event::InstitutionFounded { year, .. }
+                | Event::InstitutionClosed { year, .. }
+                | Event::AreaFounded { year, .. }
+                | Event::OutbreakBegan { year, .. }
+                | Event::OutbreakEnded { year, .. }
+                | Event::Immigrated { year, .. }
+                | Event::Emigrated { year, .. }
+                | Event::Succession { year, .. }
+                | Event::HouseFounded { year, .. } => *year,
+            };
+        }
+
+        pub fn participants(self: &Self) -> Vec<MindId> {
+            return match self {
+                Event::Birth { child, parents, .. } => {
+                    let mut ids = vec![child.clone()];
+                    ids.extend(parents.iter().cloned());
+                    ids
+                }
+                Event::Death { mind, .. }
+                | Event::Hired { mind, .. }
+                | Event::Fired { mind, .. }
+                | Event::Promoted { mind, .. }
+                | Event::ChangedEmployer { mind, .. }
+                | Event::HouseFounded { founder: mind, .. } => vec![mind.clone()],
+                Event::Partnered { minds, .. }
+                | Event::Married { minds, .. }
+                | Event::Separated { minds, .. }
+                | Event::Divorced { minds, .. } => minds.to_vec(),
+                Event::OutbreakBegan { cases, .. } => cases.clone(),
+                Event::Immigrated { minds, .. } | Event::Emigrated { minds, .. } => minds.clone(),
+                Event::Succession {
+                    ruler, predecessor, ..
+                } => {
+                    let mut ids = vec![ruler.clone()];
+                    ids.extend(predecessor.iter().cloned());
+                    ids
+                }
+                Event::InstitutionFounded { .. }
+                | Event::InstitutionClosed { .. }
+                | Event::AreaFounded { .. }
+                | Event::OutbreakEnded { .. } => Vec::new(),
+            };
+        }
+
+        // a move between employers involves both the one left and the one joined
+        pub fn institutions(self: &Self) -> Vec<InstitutionId> {
+            return match self {
+                Event::Hired { institution, .. }
+                | Event::Fired { institution, .. }
+                | Event::Promoted { institution, .. }
+                | Event::InstitutionFounded { institution, .. }
+                | Event::InstitutionClosed { institution, .. } => vec![institution.clone()],
+                Event::ChangedEmployer {
+                    from, institution, ..
+                } => vec![from.clone(), institution.clone()],
+                _ => Vec::new(),
+            };
+        }
+
+        pub fn describe(self: &Self, city: &City) -> String {
+            let mind_name = |id: &MindId| city.titled_name(id);
+            let institution_name = |id: &InstitutionId| city.institution_name(id);
+            return match self {
+                Event::Birth { child, parents, .. } => format!(
+                    "{} was born to {}",
+                    mind_name(child),
+                    parents
+                        .iter()
+                        .map(|p| mind_name(p))
+                        .collect::<Vec<String>>()
+                        .join(" and ")
+                ),
+                Event::Death { mind, .. } => {
+                    let cause = city
+                        .population
+                        .get(mind)
+                        .and_then(|m| m.cause_of_death.as_ref());
+                    if cause.is_some() {
+                        format!("{} {}", mind_name(mind), cause.unwrap().describe())
+                    } else {
+                        format!("{} died", mind_name(mind))
+                    }
+                }
+                Event::Partnered { minds, .. } => format!(
+                    "{} and {} got together",
+                    mind_name(&minds[0]),
+                    mind_name(&minds[1])
+                ),
+                Event::Married { minds, .. } => {
+                    format!("{} married {}", mind_name(&minds[0]), mind_name(&minds[1]))
+                }
+                Event::Separated { minds, .. } => format!(
+                    "{} and {} separated",
+                    mind_name(&minds[0]),
+                    mind_name(&minds[1])
+                ),
+                Event::Divorced { minds, .. } => format!(
+                    "{} and {} divorced",
+                    mind_name(&minds[0]),
+                    mind_name(&minds[1])
+                ),
+                Event::Hired {
+                    mind,
+                    institution,
+                    title,
+                    ..
+                } => format!(
+                    "{} started as {} at {}",
+                    mind_name(mind),
+                    title,
+                    institution_name(institution)
+                ),
+                Event::Fired {
+                    mind, institution, ..
+                } => format!(
+                    "{} was let go from {}",
+                    mind_name(mind),
+                    institution_name(institution)
+                ),
+                Event::Promoted {
+                    mind,
+                    institution,
+                    title,
+                    ..
+                } => format!(
+                    "{} was promoted to {} at {}",
+                    mind_name(mind),
+                    title,
+                    institution_name(institution)
+                ),
+                Event::ChangedEmployer {
+                    mind,
+                    from,
+                    institution,
+                    title,
+                    ..
+                } => format!(
+                    "{} left {} to become {} at {}",
+                    mind_name(mind),
+                    institution_name(from),
+                    title,
+                    institution_name(institution)
+                ),
+                Event::InstitutionFounded { name, .. } => format!("{} was founded", name),
+                Event::InstitutionClosed { name, .. } => format!("{} closed", name),
+                Event::AreaFounded { name, .. } => {
+                    format!("The {} district was laid out", name)
+                }
+                Event::OutbreakBegan { disease, cases, .. } => {
+                    let names: Vec<String> = cases.iter().map(|c| mind_name(c)).collect();
+                    format!(
+                        "An outbreak of {} began with {}",
+                        disease,
+                        render_list(names.iter().map(|n| n.as_str()).collect())
+                    )
+                }
+                Event::OutbreakEnded {
+                    disease,
+                    cases,
+                    deaths,
+                    ..
+                } => format!(
+                    "The outbreak of {} ended, {} fell sick and {} died",
+                    disease, cases, deaths
+                ),
+                Event::Immigrated { minds, origin, .. } => {
+                    let names: Vec<String> = minds.iter().map(|m| mind_name(m)).collect();
+                    format!(
+                        "{} arrived from {}",
+                        render_list(names.iter().map(|n| n.as_str()).collect()),
+                        origin
+                    )
+                }
+                Event::Emigrated { minds, .. } => {
+                    let names: Vec<String> = minds.iter().map(|m| mind_name(m)).collect();
+                    format!(
+                        "{} left the city",
+                        render_list(names.iter().map(|n| n.as_str()).collect())
+                    )
+                }
+                Event::Succession {
+                    ruler,
+                    predecessor,
+                    title,
+                    ..
+                } => {
+                    if predecessor.is_some() {
+                        format!(
+                            "{} succeeded {} as {}",
+                            city.mind_name(ruler),
+                            mind_name(&predecessor.unwrap()),
+                            title
+                        )
+                    } else {
+                        format!("{} became {}", city.mind_name(ruler), title)
+                    }
+                }
+                Event::HouseFounded { name, founder, .. } => {
+                    format!("{} founded {}", mind_name(founder), name)
+                }
+            };
+        }
+    }
+
+    // Events are only ever appended, in the order the systems ran, so every query below
+    // comes back in chronological order.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize, Default)]
+    pub struct Chronicle {
+        events: Vec<Event>,
+    }
+
+    impl Chronicle {
+        pub fn new() -> Chronicle {
+            return Chronicle { events: Vec::new() };
+        }
+
+        pub fn record(self: &mut Self, event: Event) {
+            self.events.push(event);
+        }
+
+        pub fn events(self: &Self) -> &Vec<Event> {
+            return &self.events;
+        }
+
+        pub fn len(self: &Self) -> usize {
+            return self.events.len();
+        }
+
+        pub fn for_mind(self: &Self, id: &MindId) -> Vec<&Event> {
+            return self
+                .events
+                .iter()
+                .filter(|e| e.participants().contains(id))
+                .collect();
+        }
+
+        pub fn for_institution(self: &Self, id: &InstitutionId) -> Vec<&Event> {
+            return self
+                .events
+                .iter()
+                .filter(|e| e.institutions().contains(id))
+                .collect();
+        }
+
+        pub fn in_year(self: &Self, year: usize) -> Vec<&Event> {
+            return self.events.iter().filter(|e| e.year() == year).collect();
+        }
+    }
+
+    impl City {
+        pub fn record_event(self: &mut Self, event: Event) {
+            self.chronicle.record(event);
+        }
+
+        pub fn mind_name(self: &Self, id: &MindId) -> String {
+            let mind = self.population.get(id);
+            return if mind.is_some() {
+                format!("{} {}", mind.unwrap().first_name, mind.unwrap().last_name)
+            } else {
+                String::from("someone forgotten")
+            };
+        }
+
+        // closed institutions are dropped by cleanup, their names live on in the chronicle
+        pub fn institution_name(self: &Self, id: &InstitutionId) -> String {
+            let institution = self.institutions.get(id);
+            if institution.is_some() {
+                return institution.unwrap().name.clone();
+            }
+            let founding = self
+                .chronicle
+                .for_institution(id)
+                .into_iter()
+                .find_map(|e| {
+                    return match e {
+                        Event::InstitutionFounded { name, .. }
+                        | Event::InstitutionClosed { name, .. } => Some(name.clone()),
+                        _ => None,
+                    };
+                });
+            return founding.unwrap_or(String::from("a forgotten institution"));
+        }
+
+        pub fn describe_year(self: &Self, year: usize) -> String {
+            let mut output = format!("## Year {}\n", year);
+            for event in self.chronicle.in_year(year) {
+                output += &format!("- {}\n", event.describe(&self));
+            }
+            return output;
+        }
+
+        pub fn describe_life(self: &Self, id: &MindId) -> String {
+            let mut output = format!("## {}\n", self.titled_name(id));
+            for event in self.chronicle.for_mind(id) {
+                output += &format!("- Year {}: {}\n", event.year(), event.describe(&self));
+            }
+            return output;
+        }
+
+        pub fn export_chronicle(self: &Self) -> String {
+            let mut output = String::new();
+            for year in 1..=self.year {
+                if self.chronicle.in_year(year).len() > 0 {
+                    output += &self.describe_year(year);
+                }
+            }
+            return output;
+        }
+    }
+
+    #[test]
+    fn test_chronicle_queries() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            60,
+            87,
+        );
+        for _i in 0..40 {
+            city.simulate_year(&dict);
+        }
+        assert!(city.chronicle.len() > 0);
+        for year in 1..=city.year {
+            assert!(city
+                .chronicle
+                .in_year(year)
+                .iter()
+                .all(|e| e.year() == year));
+        }
+        let years: Vec<usize> = city.chronicle.events().iter().map(|e| e.year()).collect();
+        assert!(years.windows(2).all(|w| w[0] <= w[1]));
+
+        let dead = city
+            .population
+            .values()
+            .find(|m| !m.alive && m.emigrated.is_none());
+        if dead.is_some() {
+            let life = city.chronicle.for_mind(&dead.unwrap().id);
+            assert!(life.iter().any(|e| matches!(e, Event::Death { .. })));
+            assert!(dead.unwrap().cause_of_death.is_some());
+        }
+        let employed = city.population.values().find(|m| m.employer.is_some());
+        if employed.is_some() {
+            let employer = employed.unwrap().employer.unwrap();
+            assert!(city.chronicle.for_institution(&employer).iter().any(
+                |e| matches!(e, Event::Hired { mind, .. } | Event::ChangedEmployer { mind, .. }
+                    if mind.eq(&employed.unwrap().id))
+            ));
+        }
+        // someone changing employer is in the history of both institutions
+        let moves: Vec<&Event> = city
+            .chronicle
+            .events()
+            .iter()
+            .filter(|e| matches!(e, Event::ChangedEmployer { .. }))
+            .collect();
+        assert!(moves.len() > 0);
+        for event in moves {
+            if let Event::ChangedEmployer {
+                from, institution, ..
+            } = event
+            {
+                assert!(city.chronicle.for_institution(from).contains(&event));
+                assert!(city.chronicle.for_institution(institution).contains(&event));
+            }
+        }
+    }
+}
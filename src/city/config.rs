@@ -0,0 +1,452 @@
+pub mod config {
+    use std::{fs, path::Path};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::city::city::Era;
+
+    // Every section falls back to its defaults, so a config file only needs the values it changes
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize, Default)]
+    #[serde(default)]
+    pub struct SimulationConfig {
+        pub demographics: DemographicConfig,
+        pub mortality: MortalityConfig,
+        pub health: HealthConfig,
+        pub epidemics: EpidemicConfig,
+        pub migration: MigrationConfig,
+        pub partners: PartnerConfig,
+        pub children: ChildConfig,
+        pub friends: FriendConfig,
+        pub employment: EmploymentConfig,
+        pub institutions: InstitutionConfig,
+        pub areas: AreaConfig,
+        pub government: GovernmentConfig,
+        pub houses: HouseConfig,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct DemographicConfig {
+        pub ambiguous_gender_chance: f32,
+        pub homosexuality_chance: f32,
+        pub asexuality_chance: f32,
+        pub bisexuality_chance: f32,
+    }
+
+    impl Default for DemographicConfig {
+        fn default() -> Self {
+            return DemographicConfig {
+                ambiguous_gender_chance: 0.1,
+                homosexuality_chance: 0.075,
+                asexuality_chance: 0.05,
+                bisexuality_chance: 0.075,
+            };
+        }
+    }
+
+    // Old age takes ((age - onset) / (age_scale * variance))^exponent * multiplier a year, where
+    // onset is onset_spread lifespan variances before the culture's average lifespan. The other
+    // causes are flat annual chances, and medicine scales illness, infant and childbirth deaths
+    // by era. The defaults keep a medieval city at about its founding size at the default
+    // birth rates.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct MortalityConfig {
+        pub onset_spread: f32,
+        pub age_scale: f32,
+        pub exponent: f32,
+        pub multiplier: f32,
+        pub infant_mortality: f32,
+        pub child_mortality: f32,
+        pub illness_rate: f32,
+        pub accident_rate: f32,
+        pub violence_rate: f32,
+        pub childbirth_mortality: f32,
+        pub modern_medicine: f32,
+        pub medieval_medicine: f32,
+        pub fantasy_medicine: f32,
+    }
+
+    impl Default for MortalityConfig {
+        fn default() -> Self {
+            return MortalityConfig {
+                onset_spread: 2.5,
+                age_scale: 3.0,
+                exponent: 2.25,
+                multiplier: 0.12,
+                infant_mortality: 0.03,
+                child_mortality: 0.002,
+                illness_rate: 0.001,
+                accident_rate: 0.001,
+                violence_rate: 0.0005,
+                childbirth_mortality: 0.01,
+                modern_medicine: 0.1,
+                medieval_medicine: 1.0,
+                fantasy_medicine: 0.7,
+            };
+        }
+    }
+
+    impl MortalityConfig {
+        pub fn old_age_chance(self: &Self, age: u32, avg_lifespan: u32, variance: u32) -> f32 {
+            let variance = (variance as f32).max(1.0);
+            let onset = avg_lifespan as f32 - self.onset_spread * variance;
+            if (age as f32) < onset {
+                return 0.0;
+            }
+            return ((age as f32 - onset) / (self.age_scale * variance)).powf(self.exponent)
+                * self.multiplier;
+        }
+
+        pub fn medicine(self: &Self, era: &Era) -> f32 {
+            return match era {
+                Era::Modern => self.modern_medicine,
+                Era::Medieval => self.medieval_medicine,
+                Era::Fantasy => self.fantasy_medicine,
+            };
+        }
+    }
+
+    // Chronic conditions start at chronic_rate a year at the culture's average lifespan, less
+    // for the young, and worsen by progression a year without care. Lethality is the annual
+    // death chance per point of severity, care scales it by care_factor. Anyone with a
+    // condition at work_threshold severity or worse can't be hired.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct HealthConfig {
+        pub chronic_rate: f32,
+        pub chronic_lethality: f32,
+        pub progression: f32,
+        pub cure_chance: f32,
+        pub injury_rate: f32,
+        pub injury_lethality: f32,
+        pub disability_chance: f32,
+        pub care_factor: f32,
+        pub work_threshold: f32,
+        pub fertility_penalty: f32,
+        pub patients_per_staff: usize,
+    }
+
+    impl Default for HealthConfig {
+        fn default() -> Self {
+            return HealthConfig {
+                chronic_rate: 0.015,
+                chronic_lethality: 0.03,
+                progression: 0.05,
+                cure_chance: 0.2,
+                injury_rate: 0.02,
+                injury_lethality: 0.01,
+                disability_chance: 0.15,
+                care_factor: 0.5,
+                work_threshold: 0.7,
+                fertility_penalty: 0.5,
+                patients_per_staff: 4,
+            };
+        }
+    }
+
+    // A disease breaks out at outbreak_chance a year while none is spreading, in seed_cases
+    // citizens. Each year the sick pass it to each friend, housemate and colleague at the era's
+    // infectiousness, then die at its lethality, halved by care as other illness is, or
+    // recover immune with a weakness_chance of being left with a lasting condition.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct EpidemicConfig {
+        pub outbreak_chance: f32,
+        pub seed_cases: usize,
+        pub weakness_chance: f32,
+        pub modern_infectiousness: f32,
+        pub medieval_infectiousness: f32,
+        pub fantasy_infectiousness: f32,
+        pub modern_lethality: f32,
+        pub medieval_lethality: f32,
+        pub fantasy_lethality: f32,
+    }
+
+    impl Default for EpidemicConfig {
+        fn default() -> Self {
+            return EpidemicConfig {
+                outbreak_chance: 0.01,
+                seed_cases: 3,
+                weakness_chance: 0.2,
+                modern_infectiousness: 0.08,
+                medieval_infectiousness: 0.15,
+                fantasy_infectiousness: 0.12,
+                modern_lethality: 0.02,
+                medieval_lethality: 0.15,
+                fantasy_lethality: 0.1,
+            };
+        }
+    }
+
+    impl EpidemicConfig {
+        pub fn infectiousness(self: &Self, era: &Era) -> f32 {
+            return match era {
+                Era::Modern => self.modern_infectiousness,
+                Era::Medieval => self.medieval_infectiousness,
+                Era::Fantasy => self.fantasy_infectiousness,
+            };
+        }
+
+        pub fn lethality(self: &Self, era: &Era) -> f32 {
+            return match era {
+                Era::Modern => self.modern_lethality,
+                Era::Medieval => self.medieval_lethality,
+                Era::Fantasy => self.fantasy_lethality,
+            };
+        }
+    }
+
+    // Prospects are the share of adults able to work who have a job. Each year
+    // arrivals_per_year singles or families arrive scaled by prospects, family_chance of them
+    // couples with up to max_children children. Unemployed heads of household and singles
+    // leave at emigration_rate, up to twice as often as prospects fall and twice again without
+    // a job. Arrivals don't grow with the city while departures do, so the two settle where
+    // they balance. minority_chance of arrivals belong to a culture from beyond the city, one
+    // already settled there or, while there are fewer than max_minorities, a new one.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct MigrationConfig {
+        pub arrivals_per_year: f32,
+        pub family_chance: f32,
+        pub max_children: usize,
+        pub emigration_rate: f32,
+        pub minority_chance: f32,
+        pub max_minorities: usize,
+    }
+
+    impl Default for MigrationConfig {
+        fn default() -> Self {
+            return MigrationConfig {
+                arrivals_per_year: 0.5,
+                family_chance: 0.4,
+                max_children: 3,
+                emigration_rate: 0.02,
+                minority_chance: 0.3,
+                max_minorities: 3,
+            };
+        }
+    }
+
+    // all rates are single annual chances
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct PartnerConfig {
+        pub marriage_rate: f32,
+        pub partner_split_rate: f32,
+        pub marriage_split_rate: f32,
+    }
+
+    impl Default for PartnerConfig {
+        fn default() -> Self {
+            return PartnerConfig {
+                marriage_rate: 0.075,
+                partner_split_rate: 0.06,
+                marriage_split_rate: 0.03,
+            };
+        }
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct ChildConfig {
+        pub child_max: usize,
+        pub partner_child_annual_chance: f32,
+        pub spouse_child_annual_chance: f32,
+    }
+
+    impl Default for ChildConfig {
+        fn default() -> Self {
+            return ChildConfig {
+                child_max: 5,
+                partner_child_annual_chance: 0.1,
+                spouse_child_annual_chance: 0.2,
+            };
+        }
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct FriendConfig {
+        pub acquaintance_lost_chance: f32,
+        pub acquaintance_to_friend_chance: f32,
+        pub friend_to_acquaintance_chance: f32,
+        pub friend_to_close_friend_chance: f32,
+        pub close_friend_to_friend_chance: f32,
+    }
+
+    impl Default for FriendConfig {
+        fn default() -> Self {
+            return FriendConfig {
+                acquaintance_lost_chance: 0.6,
+                acquaintance_to_friend_chance: 0.25,
+                friend_to_acquaintance_chance: 0.25,
+                friend_to_close_friend_chance: 0.125,
+                close_friend_to_friend_chance: 0.125,
+            };
+        }
+    }
+
+    // Staff are considered for promotion once they've served promotion_service years, and
+    // take a manager's post at another institution at job_change_chance a year
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct EmploymentConfig {
+        pub annual_firing_rate: f32,
+        pub promotion_service: usize,
+        pub job_change_chance: f32,
+    }
+
+    impl Default for EmploymentConfig {
+        fn default() -> Self {
+            return EmploymentConfig {
+                annual_firing_rate: 0.05,
+                promotion_service: 2,
+                job_change_chance: 0.5,
+            };
+        }
+    }
+
+    // Residents each institution of a service caters for, a service with fewer institutions
+    // than the city's population calls for is founded when there's nowhere left to hire.
+    // Religion is needed from half as much to half again as much as the culture's spirituality
+    // rises, education depends on the era and cities on the coast or a river trade
+    // waterside_trade times as much. Hospitals are also needed while patients go without care.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct InstitutionConfig {
+        pub food_ratio: f32,
+        pub religion_ratio: f32,
+        pub law_ratio: f32,
+        pub healthcare_ratio: f32,
+        pub modern_education_ratio: f32,
+        pub medieval_education_ratio: f32,
+        pub fantasy_education_ratio: f32,
+        pub trade_ratio: f32,
+        pub waterside_trade: f32,
+        pub leisure_ratio: f32,
+        pub underworld_ratio: f32,
+    }
+
+    impl Default for InstitutionConfig {
+        fn default() -> Self {
+            return InstitutionConfig {
+                food_ratio: 50.0,
+                religion_ratio: 120.0,
+                law_ratio: 150.0,
+                healthcare_ratio: 250.0,
+                modern_education_ratio: 120.0,
+                medieval_education_ratio: 400.0,
+                fantasy_education_ratio: 300.0,
+                trade_ratio: 200.0,
+                waterside_trade: 2.0,
+                leisure_ratio: 60.0,
+                underworld_ratio: 150.0,
+            };
+        }
+    }
+
+    impl InstitutionConfig {
+        pub fn education_ratio(self: &Self, era: &Era) -> f32 {
+            return match era {
+                Era::Modern => self.modern_education_ratio,
+                Era::Medieval => self.medieval_education_ratio,
+                Era::Fantasy => self.fantasy_education_ratio,
+            };
+        }
+    }
+
+    // sizes are in residents, an institution takes up institution_footprint residents' worth
+    // of space. Founding districts are laid out with founding_headroom spare room.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct AreaConfig {
+        pub min_size: usize,
+        pub max_size: usize,
+        pub institution_footprint: usize,
+        pub founding_headroom: f32,
+    }
+
+    impl Default for AreaConfig {
+        fn default() -> Self {
+            return AreaConfig {
+                min_size: 40,
+                max_size: 160,
+                institution_footprint: 5,
+                founding_headroom: 0.25,
+            };
+        }
+    }
+
+    // Mayors are elected every term_years, and the most outgoing of `candidates` adults drawn
+    // at random wins. A council keeps council_size seats filled and is led by its eldest member.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct GovernmentConfig {
+        pub term_years: usize,
+        pub candidates: usize,
+        pub council_size: usize,
+    }
+
+    impl Default for GovernmentConfig {
+        fn default() -> Self {
+            return GovernmentConfig {
+                term_years: 4,
+                candidates: 3,
+                council_size: 5,
+            };
+        }
+    }
+
+    // Monarchs found a house if they aren't in one already, and while fewer than max_houses
+    // houses stand a married citizen is ennobled at ennoble_chance a year
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct HouseConfig {
+        pub ennoble_chance: f32,
+        pub max_houses: usize,
+    }
+
+    impl Default for HouseConfig {
+        fn default() -> Self {
+            return HouseConfig {
+                ennoble_chance: 0.05,
+                max_houses: 4,
+            };
+        }
+    }
+
+    pub fn load_simulation_config(path: &str) -> Result<SimulationConfig, String> {
+        let contents = fs::read_to_string(Path::new(path))
+            .map_err(|e| format!("Could not read config {}: {}", path, e))?;
+        return toml::from_str(&contents)
+            .map_err(|e| format!("Config {} is malformed: {}", path, e));
+    }
+
+    #[test]
+    fn test_partial_config() {
+        let config: SimulationConfig = toml::from_str(
+            "[mortality]\nmultiplier = 0.3\n\n[institutions]\nreligion_ratio = 50.0\n",
+        )
+        .unwrap();
+        assert_eq!(config.mortality.multiplier, 0.3);
+        assert_eq!(config.mortality.exponent, 2.25);
+        assert_eq!(config.institutions.religion_ratio, 50.0);
+        assert_eq!(config.partners, PartnerConfig::default());
+    }
+
+    #[test]
+    fn test_old_age_follows_lifespan() {
+        let mortality = MortalityConfig::default();
+        for avg_lifespan in [60, 75, 90] {
+            let mut survival = 1.0;
+            let mut age = 0;
+            while survival > 0.5 {
+                age += 1;
+                survival *= 1.0 - mortality.old_age_chance(age, avg_lifespan, 10).min(1.0);
+            }
+            assert!(age.abs_diff(avg_lifespan) <= 5);
+        }
+        assert!(mortality.medicine(&Era::Modern) < mortality.medicine(&Era::Medieval));
+    }
+}
@@ -1,62 +1,200 @@
 pub mod surnames;
 pub mod culture {
-    use std::collections::{HashMap, HashSet};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        fmt,
+        sync::Arc,
+    };
 
     use procgen_templater::dictionary::{
         dictionary::Dictionary,
         word::word::{Word, WordType},
     };
-    use rand::Rng;
+    use rand::{rngs::StdRng, Rng};
+    use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
-    use crate::city::{
-        city::Era,
-        dieties::dieties::{random_dieties, Diety, DietyId},
+    use crate::{
+        city::{
+            city::{City, Era},
+            dieties::dieties::{random_dieties, Diety, DietyId},
+            population::mind::mind::MindId,
+            site::site::CitySite,
+        },
+        seeded::{
+            seeded::{content_id, random_id, random_word, random_word_without},
+            templates::templates::Templates,
+        },
     };
 
     use super::surnames::surnames::{
         random_child_surname_formats, random_marriage_surname_formats, SurnameFormat,
     };
 
-    #[derive(PartialEq, Debug, Clone)]
+    pub type CultureId = Uuid;
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct Culture {
-        pub id: Uuid,
+        pub id: CultureId,
+        // the city for its founding culture, the land its people came from for a minority
+        pub homeland: String,
         pub adult_age: u32,
         pub landlocked: bool,
+        #[serde(skip)]
         pub staple_meats: Vec<Word>,
+        #[serde(skip)]
         pub staple_plants: Vec<Word>,
         pub avg_lifespan: u32,
         pub avg_lifespan_variance: u32,
         // surname formats are layed out in a (male (or eldest), female (or youngest)) format
         pub child_surname_formats: Vec<(SurnameFormat, SurnameFormat)>,
         pub marriage_surname_formats: Vec<(SurnameFormat, SurnameFormat)>,
+        #[serde(skip)]
         pub historical_names: Vec<(Word, Word, Word)>,
         pub spirituality: f32,
-        pub dieties: HashMap<DietyId, Diety>,
+        #[serde(skip)]
+        pub dieties: BTreeMap<DietyId, Diety>,
         pub era: Era,
     }
 
-    pub fn random_culture(dictionary: &Dictionary, era: Era) -> Culture {
-        let mut rng = rand::thread_rng();
-        let landlocked = rng.gen::<f32>() > 0.5;
-
+    pub fn random_culture(
+        dictionary: &Dictionary,
+        templates: &Templates,
+        site: &CitySite,
+        era: Era,
+        rng: &mut StdRng,
+    ) -> Culture {
         return Culture {
-            id: Uuid::new_v4(),
+            id: random_id(rng),
+            homeland: String::new(),
             adult_age: 18 + ((8.0 * rng.gen::<f32>()) * -4.0) as u32,
-            landlocked,
-            staple_meats: generate_random_meats(dictionary, &landlocked),
-            staple_plants: generate_random_plants(dictionary),
-            avg_lifespan: 75 + (30.0 * rng.gen::<f32>() - 15.0) as u32,
+            landlocked: !site.coastal,
+            staple_meats: generate_random_meats(dictionary, site, rng),
+            staple_plants: generate_random_plants(dictionary, site, rng),
+            avg_lifespan: (75.0 + 30.0 * rng.gen::<f32>() - 15.0) as u32,
             avg_lifespan_variance: 10,
-            child_surname_formats: random_child_surname_formats(),
-            marriage_surname_formats: random_marriage_surname_formats(),
-            historical_names: generate_historical_figures(dictionary, &era),
+            child_surname_formats: random_child_surname_formats(rng),
+            marriage_surname_formats: random_marriage_surname_formats(rng),
+            historical_names: generate_historical_figures(dictionary, &era, rng),
             era,
-            dieties: random_dieties(&dictionary),
+            dieties: random_dieties(&dictionary, templates, rng),
             spirituality: rng.gen::<f32>(),
         };
     }
 
+    // Dictionaries rebalanced for each of the city's cultures, along with the dictionary they
+    // were rebalanced from. They're rebuilt whenever a city is founded or loaded rather than
+    // saved, and follow from the cultures, so they take no part in comparing cities.
+    #[derive(Clone, Default)]
+    pub struct CultureDictionaries {
+        raw: Option<Arc<Dictionary>>,
+        dictionaries: BTreeMap<CultureId, Arc<Dictionary>>,
+    }
+
+    impl CultureDictionaries {
+        pub fn new(dict: &Dictionary) -> CultureDictionaries {
+            return CultureDictionaries {
+                raw: Some(Arc::new(dict.clone())),
+                dictionaries: BTreeMap::new(),
+            };
+        }
+
+        pub fn add(self: &mut Self, culture: &Culture) {
+            let raw = self.raw.as_ref().unwrap();
+            self.dictionaries.insert(
+                culture.id.clone(),
+                Arc::new(rebalance_dict_for_culture(culture, raw)),
+            );
+        }
+
+        // the dictionary before any rebalancing, new cultures are drawn from it so that their
+        // words can be found again when a snapshot is loaded
+        pub fn raw(self: &Self) -> Arc<Dictionary> {
+            return self.raw.clone().unwrap();
+        }
+
+        pub fn get(self: &Self, id: &CultureId) -> Option<Arc<Dictionary>> {
+            return self.dictionaries.get(id).cloned();
+        }
+    }
+
+    impl PartialEq for CultureDictionaries {
+        fn eq(&self, _other: &Self) -> bool {
+            return true;
+        }
+    }
+
+    impl fmt::Debug for CultureDictionaries {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "CultureDictionaries({})", self.dictionaries.len())
+        }
+    }
+
+    impl City {
+        // the founding culture isn't kept among the minorities
+        pub fn culture_by_id(self: &Self, id: &CultureId) -> &Culture {
+            return self.minorities.get(id).unwrap_or(&self.culture);
+        }
+
+        pub fn culture_of(self: &Self, mind_id: &MindId) -> &Culture {
+            return self.culture_by_id(&self.population.get(mind_id).unwrap().culture);
+        }
+
+        pub fn is_minority(self: &Self, mind_id: &MindId) -> bool {
+            return self
+                .minorities
+                .contains_key(&self.population.get(mind_id).unwrap().culture);
+        }
+
+        // the dictionary names are drawn from for members of a culture
+        pub fn dictionary_for<'a>(
+            self: &'a Self,
+            dict: &'a Dictionary,
+            id: &CultureId,
+        ) -> &'a Dictionary {
+            let names = self.dictionaries.dictionaries.get(id);
+            return if names.is_some() {
+                names.unwrap().as_ref()
+            } else {
+                dict
+            };
+        }
+
+        // the founding culture's dictionary, rebuilt along with the others whenever the
+        // culture has been replaced since they were made
+        pub fn culture_dictionary(self: &mut Self, dict: &Dictionary) -> Arc<Dictionary> {
+            if self.dictionaries.get(&self.culture.id).is_none() {
+                self.refresh_dictionaries(dict);
+            }
+            return self.dictionaries.get(&self.culture.id).unwrap();
+        }
+
+        pub fn refresh_dictionaries(self: &mut Self, dict: &Dictionary) {
+            self.dictionaries = CultureDictionaries::new(dict);
+            self.dictionaries.add(&self.culture);
+            for culture in self.minorities.values() {
+                self.dictionaries.add(culture);
+            }
+        }
+
+        pub fn add_minority(self: &mut Self, culture: Culture) {
+            self.dictionaries.add(&culture);
+            self.minorities.insert(culture.id.clone(), culture);
+        }
+
+        // how many living citizens belong to each culture, the founding culture first
+        pub fn culture_counts(self: &Self) -> Vec<(&Culture, usize)> {
+            let mut counts: Vec<(&Culture, usize)> = vec![(&self.culture, 0)];
+            counts.extend(self.minorities.values().map(|c| (c, 0)));
+            for mind in self.population.values().filter(|m| m.alive) {
+                let culture = self.culture_by_id(&mind.culture);
+                let count = counts.iter_mut().find(|(c, _)| c.id.eq(&culture.id));
+                count.unwrap().1 += 1;
+            }
+            return counts;
+        }
+    }
+
     fn balance_words_to_dict<'a>(
         dict: &'a mut Dictionary,
         words: &Vec<Word>,
@@ -86,9 +224,12 @@ pub mod culture {
         let repeats = (((1.0 - (words.len() as f32 / tag_count_in_dict as f32)) * fraction)
             * tag_count_in_dict as f32) as usize;
         for word in words {
-            for _i in 0..repeats {
+            let mut word_tags: Vec<&String> = word.tags.iter().collect();
+            word_tags.sort();
+            for i in 0..repeats {
                 let mut wc = word.clone();
-                let id = Uuid::new_v4();
+                // the same copy of a word gets the same id whenever the dictionary is rebuilt
+                let id = content_id(&format!("{:?}|{}|{:?}|{}", tags, word.base, word_tags, i));
                 wc.id = id.clone();
                 dict.words.insert(id, wc);
                 for t in &word.tags {
@@ -144,117 +285,136 @@ pub mod culture {
         return dict;
     }
 
-    fn generate_random_plants(dictionary: &Dictionary) -> Vec<Word> {
+    // crops are mostly the ones suited to the site's biome, grain is grown everywhere
+    fn generate_random_plants(
+        dictionary: &Dictionary,
+        site: &CitySite,
+        rng: &mut StdRng,
+    ) -> Vec<Word> {
         let mut output: Vec<Word> = Vec::new();
-        let mut rng = rand::thread_rng();
         for _i in 0..(rng.gen::<f32>() * 4.0) as usize + 1 {
+            let crop_tags = if rng.gen::<f32>() > 0.2 {
+                site.biome.crop_tags()
+            } else {
+                vec!["Crop".to_string()]
+            };
             output.push(
-                dictionary
-                    .get_random_word((WordType::Noun, vec![vec!["Crop".to_string()]]))
+                random_word(dictionary, (WordType::Noun, vec![crop_tags]), rng)
                     .unwrap()
                     .clone(),
             )
         }
         for _i in 0..(rng.gen::<f32>() * 2.0) as usize + 1 {
             output.push(
-                dictionary
-                    .get_random_word((WordType::Noun, vec![vec!["Grain".to_string()]]))
-                    .unwrap()
-                    .clone(),
+                random_word(
+                    dictionary,
+                    (WordType::Noun, vec![vec!["Grain".to_string()]]),
+                    rng,
+                )
+                .unwrap()
+                .clone(),
             )
         }
         return output;
     }
 
-    fn generate_random_meats(dictionary: &Dictionary, landlocked: &bool) -> Vec<Word> {
+    // game comes from the site's biome, only coastal cities fish the sea
+    fn generate_random_meats(
+        dictionary: &Dictionary,
+        site: &CitySite,
+        rng: &mut StdRng,
+    ) -> Vec<Word> {
         let mut output: Vec<Word> = Vec::new();
-        let mut rng = rand::thread_rng();
         for _i in 0..(rng.gen::<f32>() * 10.0) as usize + 3 {
-            if *landlocked || rng.gen::<f32>() > 0.3 {
+            if !site.coastal || rng.gen::<f32>() > 0.3 {
                 // land creature (non carnivore)
                 // should exclude ocean creatures
                 output.push(
-                    dictionary
-                        .get_random_word_without(
-                            (
-                                WordType::Noun,
-                                vec![
-                                    vec![
-                                        "Mammal".to_string(),
-                                        "Bird".to_string(),
-                                        "Reptile".to_string(),
-                                    ],
-                                    vec!["Medium".to_string(), "Large".to_string()],
-                                ],
-                            ),
-                            vec![
-                                "Carnivore".to_string(),
-                                "Sentient".to_string(),
-                                "Ocean".to_string(),
-                                "Magical".to_string(),
-                                "Magical".to_string(),
-                            ],
-                        )
-                        .unwrap()
-                        .clone(),
+                    random_word_without(
+                        dictionary,
+                        (WordType::Noun, site.biome.game_tags()),
+                        vec![
+                            "Carnivore".to_string(),
+                            "Sentient".to_string(),
+                            "Ocean".to_string(),
+                            "Magical".to_string(),
+                            "Magical".to_string(),
+                        ],
+                        rng,
+                    )
+                    .unwrap()
+                    .clone(),
                 );
             } else {
                 // fish (can be carivore)
                 output.push(
-                    dictionary
-                        .get_random_word_without(
-                            (
-                                WordType::Noun,
-                                vec![
-                                    vec!["Fish".to_string(), "Mammal".to_string()],
-                                    vec!["Ocean".to_string()],
-                                ],
-                            ),
+                    random_word_without(
+                        dictionary,
+                        (
+                            WordType::Noun,
                             vec![
-                                "Sentient".to_string(),
-                                "Collosal".to_string(),
-                                "Magical".to_string(),
-                                "Mythical".to_string(),
+                                vec!["Fish".to_string(), "Mammal".to_string()],
+                                vec!["Ocean".to_string()],
                             ],
-                        )
-                        .unwrap()
-                        .clone(),
+                        ),
+                        vec![
+                            "Sentient".to_string(),
+                            "Collosal".to_string(),
+                            "Magical".to_string(),
+                            "Mythical".to_string(),
+                        ],
+                        rng,
+                    )
+                    .unwrap()
+                    .clone(),
                 );
             }
         }
         return output;
     }
 
-    fn generate_historical_figures(dictionary: &Dictionary, era: &Era) -> Vec<(Word, Word, Word)> {
-        let mut rng = rand::thread_rng();
+    fn generate_historical_figures(
+        dictionary: &Dictionary,
+        era: &Era,
+        rng: &mut StdRng,
+    ) -> Vec<(Word, Word, Word)> {
         let mut historical_names: Vec<(Word, Word, Word)> = Vec::new();
         for _i in 0..((rng.gen::<f32>() * 10.0) + 3.0) as usize {
             let gender = if rng.gen::<bool>() { "Male" } else { "Female" }.to_string();
-            let title = dictionary
-                .get_random_word((
+            let title = random_word(
+                dictionary,
+                (
                     WordType::Noun,
                     vec![vec!["Title".to_string()], vec![gender.clone()]],
-                ))
-                .unwrap()
-                .clone();
-            let f_name = dictionary
-                .get_random_word((
+                ),
+                rng,
+            )
+            .unwrap()
+            .clone();
+            let f_name = random_word(
+                dictionary,
+                (
                     WordType::Noun,
                     vec![
                         vec!["FirstName".to_string()],
                         vec![gender.clone()],
-                        vec![format!("{}", era)],
+                        era.tags(),
                     ],
-                ))
-                .unwrap()
-                .clone();
-            let l_name = dictionary
-                .get_random_word((
+                ),
+                rng,
+            )
+            .unwrap()
+            .clone();
+            let l_name = random_word(
+                dictionary,
+                (
                     WordType::Noun,
-                    vec![vec!["LastName".to_string()], vec![format!("{}", era)]],
-                ))
-                .unwrap()
-                .clone();
+                    vec![vec!["LastName".to_string()], era.tags()],
+                ),
+                rng,
+            )
+            .unwrap()
+            .clone();
             historical_names.push((title, f_name, l_name));
         }
         return historical_names;
@@ -262,18 +422,37 @@ pub mod culture {
 
     #[test]
     fn test_random_culture() {
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
+        };
         use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
         println!(
             "{:#?}",
-            random_culture(&build_dictionary_from_folder("./data_files"), Era::Medieval)
+            random_culture(
+                &build_dictionary_from_folder("./data_files"),
+                &build_templates_from_folder("./data_files"),
+                &CitySite::default(),
+                Era::Medieval,
+                &mut seeded_rng(0, "test", 0)
+            )
         );
     }
 
     #[test]
     fn rebalance_dict() {
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
+        };
         use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
         let dict = build_dictionary_from_folder("./data_files");
-        let culture = random_culture(&dict, Era::Medieval);
+        let templates = build_templates_from_folder("./data_files");
+        let culture = random_culture(
+            &dict,
+            &templates,
+            &CitySite::default(),
+            Era::Medieval,
+            &mut seeded_rng(0, "test", 0),
+        );
 
         let dict2 = rebalance_dict_for_culture(&culture, &dict);
         assert!(
@@ -290,4 +469,57 @@ pub mod culture {
                     .len()
         );
     }
+
+    #[test]
+    fn test_minorities() {
+        use crate::city::{city::random_city, config::config::SimulationConfig};
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut config = SimulationConfig::default();
+        config.migration.arrivals_per_year = 3.0;
+        config.migration.minority_chance = 1.0;
+        config.migration.max_minorities = 2;
+        let mut city = random_city(&dict, &templates, &config, Era::Modern, 60, 9);
+        for _i in 0..10 {
+            city.simulate_year(&dict);
+        }
+        assert!(city.minorities.len() > 0 && city.minorities.len() <= 2);
+        for mind in city.population.values().filter(|m| city.is_minority(&m.id)) {
+            let culture = city.culture_of(&mind.id);
+            assert!(mind.dieties.iter().all(|d| culture.dieties.contains_key(d)));
+            assert!(mind.print(&city).contains(&culture.homeland));
+        }
+        let counts = city.culture_counts();
+        assert_eq!(counts[0].0.id, city.culture.id);
+        assert_eq!(
+            counts.iter().map(|(_, n)| n).sum::<usize>(),
+            city.current_citizens().len()
+        );
+
+        let path = std::env::temp_dir().join("city_generator_minorities_test.json");
+        city.save(path.to_str().unwrap()).unwrap();
+        let loaded = City::load(path.to_str().unwrap(), &dict, &templates).unwrap();
+        assert_eq!(loaded.minorities, city.minorities);
+
+        // minorities come of age on their own culture's timetable, not the founders'
+        for culture in city.minorities.values_mut() {
+            culture.adult_age = city.culture.adult_age + 12;
+        }
+        let changed = city.year;
+        for _i in 0..15 {
+            city.simulate_year(&dict);
+        }
+        let mut hired = 0;
+        for mind in city.population.values().filter(|m| city.is_minority(&m.id)) {
+            let adult_age = city.culture_of(&mind.id).adult_age;
+            let first = mind.career.first().filter(|r| r.began > changed);
+            if first.is_some() {
+                assert!(first.unwrap().began as i32 - mind.year_of_birth > adult_age as i32);
+                hired += 1;
+            }
+        }
+        assert!(hired > 0);
+    }
 }
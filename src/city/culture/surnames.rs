@@ -1,8 +1,10 @@
 pub mod surnames {
+    use rand::rngs::StdRng;
     use rand::seq::SliceRandom;
     use rand::Rng;
+    use serde::{Deserialize, Serialize};
 
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct SurnameFormat {
         pre: String,
         mind_1_first_present: bool,
@@ -20,11 +22,11 @@ pub mod surnames {
             mind_1_last: String,
             mind_2_first: String,
             mind_2_last: String,
+            rng: &mut StdRng,
         ) -> String {
             // TODO - dehiphenite case
-            let mut rng = rand::thread_rng();
             let mut mind_1_last_splits: Vec<&str> = mind_1_last.split('-').into_iter().collect();
-            mind_1_last_splits.shuffle(&mut rng);
+            mind_1_last_splits.shuffle(rng);
             let mut mind_2_last_splits: Vec<&str> = mind_2_last.split('-').into_iter().collect();
 
             let all_sub_names_identicle = mind_1_last_splits
@@ -33,7 +35,7 @@ pub mod surnames {
             if all_sub_names_identicle && self.between.len() > 0 {
                 return mind_1_last_splits.first().unwrap().to_string();
             }
-            mind_2_last_splits.shuffle(&mut rng);
+            mind_2_last_splits.shuffle(rng);
             while !all_sub_names_identicle
                 && mind_1_last_splits.len() > 1
                 && mind_2_last_splits.len() > 1
@@ -41,8 +43,12 @@ pub mod surnames {
                     .first()
                     .unwrap()
                     .eq(mind_2_last_splits.first().unwrap())
+                // reshuffling can't help when every part matches, e.g. Smith-Smith
+                && mind_2_last_splits
+                    .iter()
+                    .any(|m2| m2.ne(mind_1_last_splits.first().unwrap()))
             {
-                mind_2_last_splits.shuffle(&mut rng);
+                mind_2_last_splits.shuffle(rng);
             }
             let mut output = String::new();
             output.push_str(&self.pre);
@@ -64,7 +70,9 @@ pub mod surnames {
         }
     }
 
-    pub fn random_marriage_surname_formats() -> Vec<(SurnameFormat, SurnameFormat)> {
+    pub fn random_marriage_surname_formats(
+        rng: &mut StdRng,
+    ) -> Vec<(SurnameFormat, SurnameFormat)> {
         let mut output: Vec<(SurnameFormat, SurnameFormat)> = Vec::new();
         let mut list = vec![
             // name1-name2/name2-name1
@@ -151,15 +159,14 @@ pub mod surnames {
                 },
             ),
         ];
-        let mut rng = rand::thread_rng();
-        list.shuffle(&mut rng);
+        list.shuffle(rng);
         for i in 0..if rng.gen::<f32>() < 0.5 { 2 } else { 3 } {
             output.push(list.get(i).unwrap().clone());
         }
         return output;
     }
 
-    pub fn random_child_surname_formats() -> Vec<(SurnameFormat, SurnameFormat)> {
+    pub fn random_child_surname_formats(rng: &mut StdRng) -> Vec<(SurnameFormat, SurnameFormat)> {
         let mut output: Vec<(SurnameFormat, SurnameFormat)> = Vec::new();
         let mut list = vec![
             // father
@@ -327,8 +334,7 @@ pub mod surnames {
                 },
             ),
         ];
-        let mut rng = rand::thread_rng();
-        list.shuffle(&mut rng);
+        list.shuffle(rng);
         for i in 0..if rng.gen::<f32>() < 0.5 { 2 } else { 3 } {
             output.push(list.get(i).unwrap().clone());
         }
@@ -337,6 +343,8 @@ pub mod surnames {
 
     #[test]
     fn test_surname_formatter() {
+        use crate::seeded::seeded::seeded_rng;
+        let mut rng = seeded_rng(0, "test", 0);
         let t_format = SurnameFormat {
             pre: String::new(),
             mind_1_first_present: true,
@@ -352,13 +360,16 @@ pub mod surnames {
                 "Random".to_string(),
                 "Surname".to_string(),
                 "Random2".to_string(),
-                "Surname2".to_string()
+                "Surname2".to_string(),
+                &mut rng
             )
         );
     }
     #[test]
     fn test_random_child_surname_formatter() {
-        let t = random_child_surname_formats();
+        use crate::seeded::seeded::seeded_rng;
+        let mut rng = seeded_rng(0, "test", 0);
+        let t = random_child_surname_formats(&mut rng);
         let t_format = t.first().unwrap();
         println!(
             "Child Surname Test: {}",
@@ -366,7 +377,8 @@ pub mod surnames {
                 "Random".to_string(),
                 "Surname".to_string(),
                 "Random2".to_string(),
-                "Surname2".to_string()
+                "Surname2".to_string(),
+                &mut rng
             )
         );
         println!(
@@ -375,14 +387,17 @@ pub mod surnames {
                 "Random".to_string(),
                 "Surname".to_string(),
                 "Random2".to_string(),
-                "Surname2".to_string()
+                "Surname2".to_string(),
+                &mut rng
             )
         );
     }
 
     #[test]
     fn test_random_marriage_surname_formatter() {
-        let t = random_marriage_surname_formats();
+        use crate::seeded::seeded::seeded_rng;
+        let mut rng = seeded_rng(0, "test", 0);
+        let t = random_marriage_surname_formats(&mut rng);
         let t_format = t.first().unwrap();
         println!(
             "Marriage Surname Test: {}",
@@ -390,7 +405,8 @@ pub mod surnames {
                 "Random".to_string(),
                 "Surname".to_string(),
                 "Random2".to_string(),
-                "Surname2".to_string()
+                "Surname2".to_string(),
+                &mut rng
             )
         );
         println!(
@@ -399,7 +415,8 @@ pub mod surnames {
                 "Random".to_string(),
                 "Surname".to_string(),
                 "Random2".to_string(),
-                "Surname2".to_string()
+                "Surname2".to_string(),
+                &mut rng
             )
         );
     }
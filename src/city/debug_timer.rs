@@ -1,16 +1,20 @@
 pub mod debub_timer {
-    use std::time::Instant;
+    use std::time::{Duration, Instant};
 
     #[derive(PartialEq, Debug, Clone)]
     pub struct DebugTimer {
         pub start: Instant,
         pub times_millis: Vec<(String, u128)>,
+        pub system_start: Instant,
+        pub system_times: Vec<(String, Duration)>,
     }
 
     pub fn new_debug_timer() -> DebugTimer {
         return DebugTimer {
             start: Instant::now(),
             times_millis: vec![],
+            system_start: Instant::now(),
+            system_times: vec![],
         };
     }
 
@@ -26,15 +30,34 @@ pub mod debub_timer {
             }
             self.start = Instant::now();
         }
+        // systems are timed separately from the timestamps they add themselves, so a
+        // system's total includes all of its own steps
+        pub fn start_system(self: &mut Self) {
+            self.system_start = Instant::now();
+        }
+        pub fn end_system(self: &mut Self, name: &str) {
+            let elapsed = self.system_start.elapsed();
+            let matching = self.system_times.iter_mut().find(|(n, _t)| n.eq(&name));
+            if matching.is_some() {
+                matching.unwrap().1 += elapsed;
+            } else {
+                self.system_times.push((name.to_string(), elapsed));
+            }
+        }
         pub fn clear<'a>(self: &'a mut Self) {
             self.start = Instant::now();
             self.times_millis = vec![];
+            self.system_times = vec![];
         }
         pub fn print(self: &Self) {
             println!("Timer Debug:");
             for (label, time) in &self.times_millis {
                 println!("  {:<30} {time}ms", format!("{label}:"))
             }
+            println!("Systems:");
+            for (label, time) in &self.system_times {
+                println!("  {:<30} {}ms", format!("{label}:"), time.as_millis())
+            }
         }
     }
 }
@@ -0,0 +1,270 @@
+pub mod demand {
+    use std::fmt;
+
+    use procgen_templater::dictionary::dictionary::Dictionary;
+    use rand::{rngs::StdRng, seq::SliceRandom};
+
+    use crate::city::{
+        city::City,
+        institutions::institutions::{generate_institution, InsitutionType, Institution},
+    };
+
+    // what a city needs its institutions for
+    #[derive(PartialEq, Debug, Clone)]
+    pub enum Service {
+        Food,
+        Religion,
+        Law,
+        Healthcare,
+        Education,
+        Trade,
+        Leisure,
+        Underworld,
+    }
+
+    impl fmt::Display for Service {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    const SERVICES: [Service; 8] = [
+        Service::Food,
+        Service::Religion,
+        Service::Law,
+        Service::Healthcare,
+        Service::Education,
+        Service::Trade,
+        Service::Leisure,
+        Service::Underworld,
+    ];
+
+    impl InsitutionType {
+        pub fn service(self: &Self) -> Service {
+            return match self {
+                InsitutionType::GeneralRetail
+                | InsitutionType::SpecialistRetail
+                | InsitutionType::Restaurant => Service::Food,
+                InsitutionType::Altar | InsitutionType::Chapel => Service::Religion,
+                InsitutionType::Hall | InsitutionType::PoliceStation | InsitutionType::Prison => {
+                    Service::Law
+                }
+                InsitutionType::Hospital => Service::Healthcare,
+                InsitutionType::School | InsitutionType::Library | InsitutionType::University => {
+                    Service::Education
+                }
+                InsitutionType::Dock | InsitutionType::Bank | InsitutionType::Office => {
+                    Service::Trade
+                }
+                InsitutionType::Pub
+                | InsitutionType::Hotel
+                | InsitutionType::Theater
+                | InsitutionType::Casino
+                | InsitutionType::Club
+                | InsitutionType::Gym
+                | InsitutionType::TattooParlor => Service::Leisure,
+                InsitutionType::ShadyBar | InsitutionType::Fence => Service::Underworld,
+            };
+        }
+    }
+
+    // how many institutions a service needs, how many it has and the types that could be
+    // founded for it
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct Demand {
+        pub service: Service,
+        pub wanted: usize,
+        pub existing: usize,
+        pub foundable: Vec<InsitutionType>,
+    }
+
+    impl Demand {
+        // a service whose types can't be founded in the city has no demand left to meet
+        pub fn unmet(self: &Self) -> usize {
+            if self.foundable.len() == 0 {
+                return 0;
+            }
+            return self.wanted.saturating_sub(self.existing);
+        }
+    }
+
+    impl City {
+        fn institutions_of(self: &Self, service: &Service) -> usize {
+            return self
+                .institutions
+                .values()
+                .filter(|i| i.institution_type.service().eq(service))
+                .count();
+        }
+
+        fn wanted(self: &Self, service: &Service, residents: f32) -> usize {
+            let config = &self.config.institutions;
+            let waterside = if self.site.coastal || self.site.river {
+                config.waterside_trade
+            } else {
+                1.0
+            };
+            let wanted = match service {
+                Service::Food => residents / config.food_ratio,
+                Service::Religion => {
+                    residents * (0.5 + self.culture.spirituality) / config.religion_ratio
+                }
+                Service::Law => residents / config.law_ratio,
+                Service::Healthcare => residents / config.healthcare_ratio,
+                Service::Education => residents / config.education_ratio(&self.culture.era),
+                Service::Trade => residents * waterside / config.trade_ratio,
+                Service::Leisure => residents / config.leisure_ratio,
+                Service::Underworld => residents / config.underworld_ratio,
+            }
+            .round() as usize;
+            if service.eq(&Service::Healthcare) && self.care_shortfall() > 0 {
+                return wanted.max(self.institutions_of(service) + 1);
+            }
+            return wanted;
+        }
+
+        pub fn demand(self: &Self) -> Vec<Demand> {
+            let residents = self.current_citizens().len() as f32;
+            return SERVICES
+                .iter()
+                .map(|service| Demand {
+                    service: service.clone(),
+                    wanted: self.wanted(service, residents),
+                    existing: self.institutions_of(service),
+                    foundable: InsitutionType::all()
+                        .into_iter()
+                        .filter(|t| t.service().eq(service) && self.can_found(t))
+                        .collect(),
+                })
+                .collect();
+        }
+
+        // An institution for the service with the most unmet demand, of whichever of its types
+        // the city has fewest of. Nothing is founded while every need is met. The demand is
+        // worked out once by the caller and counts the institution as founded.
+        pub fn needed_institution(
+            self: &Self,
+            demand: &mut Vec<Demand>,
+            dict: &Dictionary,
+            rng: &mut StdRng,
+        ) -> Option<Institution> {
+            let most_needed = demand.iter_mut().filter(|d| d.unmet() > 0).fold(
+                None,
+                |most: Option<&mut Demand>, d| {
+                    if most.is_none() || d.unmet() > most.as_ref().unwrap().unmet() {
+                        return Some(d);
+                    }
+                    return most;
+                },
+            );
+            if most_needed.is_none() {
+                return None;
+            }
+            let most_needed = most_needed.unwrap();
+            most_needed.existing += 1;
+            let foundable = &most_needed.foundable;
+            let count = |t: &InsitutionType| {
+                self.institutions
+                    .values()
+                    .filter(|i| i.institution_type.eq(t))
+                    .count()
+            };
+            let fewest = foundable.iter().map(count).min().unwrap();
+            let types: Vec<&InsitutionType> =
+                foundable.iter().filter(|t| count(t) == fewest).collect();
+            let institution_type = (*types.choose(rng).unwrap()).clone();
+            return Some(generate_institution(dict, self, institution_type, rng));
+        }
+
+        pub fn export_demand(self: &Self) -> String {
+            let mut output = String::new();
+            for demand in self.demand() {
+                output += &format!(
+                    "{}: {} of {} wanted{}  \n",
+                    demand.service,
+                    demand.existing,
+                    demand.wanted,
+                    if demand.unmet() > 0 {
+                        format!(", {} unmet", demand.unmet())
+                    } else if demand.foundable.len() == 0 && demand.wanted > demand.existing {
+                        String::from(", none can be founded")
+                    } else {
+                        String::new()
+                    }
+                );
+            }
+            return output;
+        }
+    }
+
+    #[test]
+    fn test_founding_follows_demand() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut config = SimulationConfig::default();
+        config.institutions.religion_ratio = 10.0;
+        let mut city = random_city(&dict, &templates, &config, Era::Medieval, 150, 13);
+        for _i in 0..5 {
+            city.simulate_year(&dict);
+        }
+        let demand = city.demand();
+        let religion = demand
+            .iter()
+            .find(|d| d.service.eq(&Service::Religion))
+            .unwrap();
+        // a city founds for its most pressing need first
+        assert!(demand.iter().all(|d| d.existing <= religion.existing));
+        for d in &demand {
+            if d.unmet() == 0 && d.foundable.len() > 0 {
+                assert!(d.existing <= d.wanted.max(1) + 1 || d.service.eq(&Service::Healthcare));
+            }
+        }
+        let docks = city
+            .institutions
+            .values()
+            .filter(|i| i.institution_type.eq(&InsitutionType::Dock))
+            .count();
+        assert!(docks == 0 || city.site.coastal || city.site.river);
+        assert!(city.export_demand().contains("Religion"));
+
+        // on the coast with trade wanted, founding meets every need and then stops, and a dock
+        // is among what gets founded
+        assert_eq!(docks, 0);
+        city.site.coastal = true;
+        city.config.institutions.trade_ratio = 10.0;
+        let mut demand = city.demand();
+        let unmet: usize = demand.iter().map(|d| d.unmet()).sum();
+        let trade = demand
+            .iter()
+            .find(|d| d.service.eq(&Service::Trade))
+            .unwrap();
+        assert!(trade.unmet() > 0 && trade.foundable.contains(&InsitutionType::Dock));
+        let mut rng = city.rng("test");
+        let mut founded = 0;
+        loop {
+            let institution = city.needed_institution(&mut demand, &dict, &mut rng);
+            if institution.is_none() {
+                break;
+            }
+            let institution = institution.unwrap();
+            city.institutions
+                .insert(institution.id.clone(), institution);
+            founded += 1;
+        }
+        assert_eq!(founded, unmet);
+        assert!(demand.iter().all(|d| d.unmet() == 0));
+        assert!(city
+            .needed_institution(&mut demand, &dict, &mut rng)
+            .is_none());
+        assert!(city
+            .institutions
+            .values()
+            .any(|i| i.institution_type.eq(&InsitutionType::Dock)));
+    }
+}
@@ -1,16 +1,20 @@
 pub mod dieties {
 
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
 
     use crate::{
         city::city::Era,
         grammar::grammar::{a_or_an, render_list},
+        seeded::{
+            seeded::{random_id, random_word, random_word_without},
+            templates::templates::Templates,
+        },
     };
     use procgen_templater::dictionary::{
         dictionary::Dictionary,
         word::word::{Word, WordType},
     };
-    use rand::Rng;
+    use rand::{rngs::StdRng, Rng};
     use uuid::Uuid;
 
     pub type DietyId = Uuid;
@@ -37,26 +41,30 @@ pub mod dieties {
         }
     }
 
-    pub fn random_dieties(dict: &Dictionary) -> HashMap<DietyId, Diety> {
-        let mut output: HashMap<DietyId, Diety> = HashMap::new();
+    pub fn random_dieties(
+        dict: &Dictionary,
+        templates: &Templates,
+        rng: &mut StdRng,
+    ) -> BTreeMap<DietyId, Diety> {
+        let mut output: BTreeMap<DietyId, Diety> = BTreeMap::new();
         for _i in 0..20 {
-            let d = random_diety(&dict);
+            let d = random_diety(&dict, templates, rng);
             output.insert(d.id.clone(), d);
         }
         return output;
     }
 
-    pub fn random_diety(dict: &Dictionary) -> Diety {
-        let form2_template = dict
-            .get_random_template(vec![vec![String::from("AncientCreature")]])
+    pub fn random_diety(dict: &Dictionary, templates: &Templates, rng: &mut StdRng) -> Diety {
+        let form2_template = templates
+            .get_random_template(vec![vec![String::from("AncientCreature")]], rng)
             .unwrap();
-        let name = dict
-            .get_random_word((WordType::Noun, vec![vec!["Name".to_string()]]))
+        let name = random_word(dict, (WordType::Noun, vec![vec!["Name".to_string()]]), rng)
             .unwrap()
             .base
             .to_string();
-        let quality = dict
-            .get_random_word((
+        let quality = random_word(
+            dict,
+            (
                 WordType::Adjective,
                 vec![vec![
                     String::from("Age"),
@@ -67,44 +75,50 @@ pub mod dieties {
                     String::from("Colour"),
                     String::from("Build"),
                 ]],
-            ))
-            .unwrap()
-            .base
-            .to_string();
+            ),
+            rng,
+        )
+        .unwrap()
+        .base
+        .to_string();
         return Diety {
-            id: Uuid::new_v4(),
-            realms: random_diety_realms(&dict, None),
+            id: random_id(rng),
+            realms: random_diety_realms(&dict, None, rng),
             name: format!("{} the {}", name, quality),
-            form: dict
-                .get_random_word_without(
-                    (WordType::Noun, vec![vec!["Creature".to_string()]]),
-                    vec!["Mythical".to_string()],
-                )
-                .unwrap()
-                .clone(),
-            form2: dict.render_template(&form2_template.id).unwrap(),
+            form: random_word_without(
+                dict,
+                (WordType::Noun, vec![vec!["Creature".to_string()]]),
+                vec!["Mythical".to_string()],
+                rng,
+            )
+            .unwrap()
+            .clone(),
+            form2: form2_template.render(&dict, rng).unwrap(),
         };
     }
 
-    fn random_diety_realms(dict: &Dictionary, era: Option<&Era>) -> Vec<Word> {
+    fn random_diety_realms(dict: &Dictionary, era: Option<&Era>, rng: &mut StdRng) -> Vec<Word> {
         let full_era = if era.is_some() {
             era.unwrap().to_string()
         } else {
             Era::Medieval.to_string()
         };
         let mut output: Vec<Word> = Vec::new();
-        let mut rng = rand::thread_rng();
         for mut _j in 0..2 {
-            let base = dict
-                .get_random_word((
+            let base = random_word(
+                dict,
+                (
                     WordType::Noun,
                     vec![vec!["DivineConcept".to_string()], vec![full_era.clone()]],
-                ))
-                .unwrap();
+                ),
+                rng,
+            )
+            .unwrap();
             // output.push(base.clone());
 
             for _i in 0..(if rng.gen::<bool>() { 1 } else { 2 }) {
-                let next_base = dict.get_random_word_without(
+                let next_base = random_word_without(
+                    dict,
                     (
                         WordType::Noun,
                         vec![
@@ -117,6 +131,7 @@ pub mod dieties {
                         .iter()
                         .map(|w| format!("Without{}", w.base))
                         .collect(),
+                    rng,
                 );
                 if next_base.is_some() {
                     let nb = next_base.unwrap();
@@ -134,10 +149,18 @@ pub mod dieties {
 
     #[test]
     fn test_gen_diety_concepts() {
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
+        };
         use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
         let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut rng = seeded_rng(0, "test", 0);
         for _i in 0..25 {
-            println!("{}", random_diety(&dict).render_summary());
+            println!(
+                "{}",
+                random_diety(&dict, &templates, &mut rng).render_summary()
+            );
         }
     }
 }
@@ -0,0 +1,295 @@
+pub mod epidemic {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use crate::city::{
+        chronicle::chronicle::Event,
+        city::{City, Era},
+        parallel::parallel::{phase_seed, propose_for_minds},
+        population::mind::{
+            health::health::{Condition, ConditionKind},
+            mind::{CauseOfDeath, MindId},
+            relations::relations::RelationVerb,
+        },
+    };
+
+    const MODERN_DISEASES: [&str; 5] = [
+        "influenza",
+        "cholera",
+        "typhoid",
+        "measles",
+        "the new fever",
+    ];
+    const MEDIEVAL_DISEASES: [&str; 5] = [
+        "the Sweating Sickness",
+        "the Red Death",
+        "the Grey Pox",
+        "the Bloody Flux",
+        "the Shaking Fever",
+    ];
+    const FANTASY_DISEASES: [&str; 5] = [
+        "the Withering",
+        "the Grey Pox",
+        "the Sleeping Rot",
+        "the Shaking Fever",
+        "Stonelung",
+    ];
+
+    // severity of an infection while it runs, high enough to keep the sick from work
+    const INFECTION_SEVERITY: f32 = 0.8;
+
+    // a disease's run through the city, from its first cases until nobody is left sick
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Epidemic {
+        pub disease: String,
+        pub began: usize,
+        pub ended: Option<usize>,
+        pub cases: usize,
+        pub deaths: usize,
+        // survivors don't catch it again
+        pub immune: BTreeSet<MindId>,
+    }
+
+    enum Fate {
+        Dies,
+        Recovers { weakness: Option<f32> },
+    }
+
+    impl City {
+        pub fn active_epidemic(self: &Self) -> Option<&Epidemic> {
+            return self.epidemics.iter().find(|e| e.ended.is_none());
+        }
+
+        // the living friends, housemates and colleagues a mind could pass a disease to
+        pub fn contacts_of(self: &Self, id: &MindId) -> BTreeSet<MindId> {
+            let mind = self.population.get(id).unwrap();
+            let mut contacts: BTreeSet<MindId> = BTreeSet::new();
+            for verb in [RelationVerb::Friend, RelationVerb::CloseFriend] {
+                contacts.extend(mind.get_relations(verb));
+            }
+            let household = self.household_of(id);
+            if household.is_some() {
+                contacts.extend(household.unwrap().members.iter().cloned());
+            }
+            if mind.employer.is_some() {
+                let employer = self.institutions.get(&mind.employer.unwrap());
+                if employer.is_some() {
+                    contacts.extend(employer.unwrap().staff.keys().cloned());
+                }
+            }
+            contacts.remove(id);
+            contacts.retain(|c| self.population.get(c).is_some_and(|m| m.alive));
+            return contacts;
+        }
+
+        fn infect(self: &mut Self, id: &MindId, disease: &str) {
+            let year = self.year;
+            self.population
+                .get_mut(id)
+                .unwrap()
+                .health
+                .conditions
+                .push(Condition {
+                    kind: ConditionKind::Infection,
+                    name: disease.to_string(),
+                    severity: INFECTION_SEVERITY,
+                    since: year,
+                    until: Some(year + 1),
+                });
+        }
+
+        fn break_out(self: &mut Self, rng: &mut StdRng) {
+            let diseases = match self.culture.era {
+                Era::Modern => MODERN_DISEASES,
+                Era::Medieval => MEDIEVAL_DISEASES,
+                Era::Fantasy => FANTASY_DISEASES,
+            };
+            let disease = diseases.choose(rng).unwrap().to_string();
+            let mut citizens = self.current_citizens();
+            citizens.shuffle(rng);
+            let cases: Vec<MindId> = citizens
+                .into_iter()
+                .take(self.config.epidemics.seed_cases)
+                .collect();
+            if cases.len() == 0 {
+                return;
+            }
+            for id in &cases {
+                self.infect(id, &disease);
+            }
+            self.epidemics.push(Epidemic {
+                disease: disease.clone(),
+                began: self.year,
+                ended: None,
+                cases: cases.len(),
+                deaths: 0,
+                immune: BTreeSet::new(),
+            });
+            self.record_event(Event::OutbreakBegan {
+                year: self.year,
+                disease,
+                cases,
+            });
+        }
+
+        // Those who fell sick last year pass the disease on to their contacts, then die or
+        // recover. A new disease can only break out once the last one has run its course.
+        pub fn spread_epidemics(self: &mut Self, rng: &mut StdRng) {
+            let active = self.epidemics.iter().position(|e| e.ended.is_none());
+            if active.is_none() {
+                if rng.gen::<f32>() < self.config.epidemics.outbreak_chance {
+                    self.break_out(rng);
+                }
+                self.add_timestamp("spread epidemics");
+                return;
+            }
+            let active = active.unwrap();
+            let disease = self.epidemics[active].disease.clone();
+            let infectiousness = self.config.epidemics.infectiousness(&self.culture.era);
+            let lethality = self.config.epidemics.lethality(&self.culture.era);
+            let weakness_chance = self.config.epidemics.weakness_chance;
+            let year = self.year;
+            let sick: Vec<MindId> = self
+                .population
+                .values()
+                .filter(|m| m.alive && m.health.infection().is_some_and(|c| c.since < year))
+                .map(|m| m.id.clone())
+                .collect();
+            let contacts: BTreeMap<MindId, BTreeSet<MindId>> = sick
+                .iter()
+                .map(|id| (id.clone(), self.contacts_of(id)))
+                .collect();
+
+            let population = &self.population;
+            let immune = &self.epidemics[active].immune;
+            let health_config = &self.config.health;
+            let exposures = propose_for_minds(&sick, phase_seed(rng), |id, rng| {
+                return contacts
+                    .get(id)
+                    .unwrap()
+                    .iter()
+                    .filter(|c| {
+                        population.get(c).unwrap().health.infection().is_none()
+                            && !immune.contains(c)
+                    })
+                    .filter(|_c| rng.gen::<f32>() < infectiousness)
+                    .cloned()
+                    .collect::<Vec<MindId>>();
+            });
+            let fates = propose_for_minds(&sick, phase_seed(rng), |id, rng| {
+                let health = &population.get(id).unwrap().health;
+                if rng.gen::<f32>() < lethality * health.care_factor(health_config) {
+                    return (id.clone(), Fate::Dies);
+                }
+                let weakness = if rng.gen::<f32>() < weakness_chance {
+                    Some(rng.gen_range(0.2..0.5))
+                } else {
+                    None
+                };
+                return (id.clone(), Fate::Recovers { weakness });
+            });
+
+            let newly_sick: BTreeSet<MindId> = exposures.into_iter().flatten().collect();
+            for id in &newly_sick {
+                self.infect(id, &disease);
+            }
+            self.epidemics[active].cases += newly_sick.len();
+            for (id, fate) in fates {
+                self.population
+                    .get_mut(&id)
+                    .unwrap()
+                    .health
+                    .conditions
+                    .retain(|c| !c.kind.eq(&ConditionKind::Infection));
+                match fate {
+                    Fate::Dies => {
+                        self.kill_mind(&id, CauseOfDeath::Epidemic(disease.clone()));
+                        self.epidemics[active].deaths += 1;
+                    }
+                    Fate::Recovers { weakness } => {
+                        self.epidemics[active].immune.insert(id.clone());
+                        if weakness.is_some() {
+                            self.population
+                                .get_mut(&id)
+                                .unwrap()
+                                .health
+                                .conditions
+                                .push(Condition {
+                                    kind: ConditionKind::Chronic,
+                                    name: format!("a weakness left by {}", disease),
+                                    severity: weakness.unwrap(),
+                                    since: year,
+                                    until: None,
+                                });
+                        }
+                    }
+                }
+            }
+
+            let still_sick = self
+                .population
+                .values()
+                .any(|m| m.alive && m.health.infection().is_some());
+            if !still_sick {
+                let epidemic = &mut self.epidemics[active];
+                epidemic.ended = Some(year);
+                let event = Event::OutbreakEnded {
+                    year,
+                    disease,
+                    cases: epidemic.cases,
+                    deaths: epidemic.deaths,
+                };
+                self.record_event(event);
+            }
+            self.add_timestamp("spread epidemics");
+        }
+    }
+
+    #[test]
+    fn test_epidemics_spread_and_end() {
+        use crate::city::{city::random_city, config::config::SimulationConfig};
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut config = SimulationConfig::default();
+        config.epidemics.outbreak_chance = 1.0;
+        config.epidemics.medieval_infectiousness = 0.5;
+        config.epidemics.medieval_lethality = 0.4;
+        let mut city = random_city(&dict, &templates, &config, Era::Medieval, 120, 31);
+        for _i in 0..20 {
+            city.simulate_year(&dict);
+        }
+        assert!(city.epidemics.len() > 0);
+        assert!(city.epidemics.iter().filter(|e| e.ended.is_none()).count() <= 1);
+        for epidemic in &city.epidemics {
+            assert!(epidemic.immune.len() + epidemic.deaths <= epidemic.cases);
+        }
+
+        let deaths: usize = city.epidemics.iter().map(|e| e.deaths).sum();
+        let epidemic_dead = city
+            .population
+            .values()
+            .filter(|m| matches!(m.cause_of_death, Some(CauseOfDeath::Epidemic(_))))
+            .count();
+        assert_eq!(deaths, epidemic_dead);
+
+        let ended_events = city
+            .chronicle
+            .events()
+            .iter()
+            .filter(|e| matches!(e, Event::OutbreakEnded { .. }))
+            .count();
+        assert_eq!(
+            ended_events,
+            city.epidemics.iter().filter(|e| e.ended.is_some()).count()
+        );
+        for mind in city.population.values().filter(|m| m.alive) {
+            if mind.health.infection().is_some() {
+                assert!(city.active_epidemic().is_some());
+            }
+        }
+    }
+}
@@ -0,0 +1,576 @@
+pub mod government {
+    use std::{collections::BTreeSet, fmt};
+
+    use procgen_templater::dictionary::{dictionary::Dictionary, word::word::WordType};
+    use rand::{rngs::StdRng, seq::SliceRandom};
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        city::{
+            chronicle::chronicle::Event,
+            city::{City, Era},
+            culture::culture::Culture,
+            institutions::institutions::{generate_institution, InsitutionType, InstitutionId},
+            population::mind::{
+                mind::{Gender, MindId},
+                relations::relations::RelationVerb,
+            },
+        },
+        seeded::seeded::random_word,
+    };
+
+    // how the city is ruled, passed down in the medieval era, elected in the modern one and
+    // by a council of its elders in the fantasy one
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub enum Office {
+        Monarch,
+        Mayor,
+        Council,
+    }
+
+    impl Office {
+        pub fn for_era(era: &Era) -> Office {
+            return match era {
+                Era::Medieval => Office::Monarch,
+                Era::Modern => Office::Mayor,
+                Era::Fantasy => Office::Council,
+            };
+        }
+    }
+
+    impl fmt::Display for Office {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    // a title in its male and female forms
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Title {
+        pub male: String,
+        pub female: String,
+    }
+
+    impl Title {
+        fn same(title: &str) -> Title {
+            return Title {
+                male: title.to_string(),
+                female: title.to_string(),
+            };
+        }
+        pub fn for_gender(self: &Self, gender: &Gender) -> &String {
+            return match gender {
+                Gender::Female => &self.female,
+                _ => &self.male,
+            };
+        }
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Reign {
+        pub ruler: MindId,
+        pub title: String,
+        pub began: usize,
+        pub ended: Option<usize>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Government {
+        pub office: Office,
+        // the hall the city is ruled from
+        pub seat: Option<InstitutionId>,
+        pub ruler: Option<MindId>,
+        // first in a monarch's line of succession
+        pub heir: Option<MindId>,
+        pub council: BTreeSet<MindId>,
+        pub ruler_title: Title,
+        pub heir_title: Option<Title>,
+        // held by a monarch's family or a council's members
+        pub honoured_title: Option<Title>,
+        pub next_election: usize,
+        pub reigns: Vec<Reign>,
+    }
+
+    // Monarchs take their titles from the Royal or Duchal theme of one of the culture's
+    // historical figures, or from either theme when none of them held such a title
+    pub fn new_government(dict: &Dictionary, culture: &Culture, rng: &mut StdRng) -> Government {
+        let office = Office::for_era(&culture.era);
+        let (ruler_title, heir_title, honoured_title) = match office {
+            Office::Monarch => {
+                let mut themes: Vec<&str> = culture
+                    .historical_names
+                    .iter()
+                    .filter_map(|(title, _, _)| {
+                        ["Royal", "Duchal"]
+                            .into_iter()
+                            .find(|theme| title.tags.contains(*theme))
+                    })
+                    .collect();
+                if themes.len() == 0 {
+                    themes = vec!["Royal", "Duchal"];
+                }
+                let theme = *themes.choose(rng).unwrap();
+                (
+                    themed_title(dict, "Ruler", theme, rng).unwrap_or(Title {
+                        male: String::from("King"),
+                        female: String::from("Queen"),
+                    }),
+                    themed_title(dict, "Heir", theme, rng),
+                    themed_title(dict, "Honoured", theme, rng),
+                )
+            }
+            Office::Mayor => (Title::same("Mayor"), None, None),
+            Office::Council => (
+                Title::same("Speaker of the Council"),
+                None,
+                Some(Title::same("Councillor")),
+            ),
+        };
+        return Government {
+            office,
+            seat: None,
+            ruler: None,
+            heir: None,
+            council: BTreeSet::new(),
+            ruler_title,
+            heir_title,
+            honoured_title,
+            next_election: 0,
+            reigns: Vec::new(),
+        };
+    }
+
+    fn themed_title(dict: &Dictionary, rank: &str, theme: &str, rng: &mut StdRng) -> Option<Title> {
+        let mut forms: Vec<String> = Vec::new();
+        for gender in ["Male", "Female"] {
+            let word = random_word(
+                dict,
+                (
+                    WordType::Noun,
+                    vec![
+                        vec![rank.to_string()],
+                        vec![theme.to_string()],
+                        vec![gender.to_string()],
+                    ],
+                ),
+                rng,
+            );
+            if word.is_none() {
+                return None;
+            }
+            forms.push(word.unwrap().base.clone());
+        }
+        return Some(Title {
+            male: forms[0].clone(),
+            female: forms[1].clone(),
+        });
+    }
+
+    impl City {
+        // The year's government business. The government is seated in the city's hall,
+        // founding one if needed, a ruler who died or left during the year is succeeded and
+        // the heir or council is brought up to date.
+        pub fn update_government(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            if self.government.is_none() {
+                self.government = Some(new_government(dict, &self.culture, rng));
+            }
+            let mut government = self.government.take().unwrap();
+            self.seat_government(&mut government, dict, rng);
+
+            let in_office = government.ruler.is_some()
+                && self
+                    .population
+                    .get(&government.ruler.unwrap())
+                    .unwrap()
+                    .alive;
+            let successor = match government.office {
+                Office::Monarch if !in_office => {
+                    let heir = government
+                        .heir
+                        .filter(|h| self.population.get(h).unwrap().alive);
+                    let line = if government.ruler.is_some() {
+                        self.line_of_succession(&government.ruler.unwrap())
+                    } else {
+                        Vec::new()
+                    };
+                    heir.or(line.first().cloned())
+                        .or_else(|| self.new_line(&government, rng))
+                }
+                Office::Mayor if !in_office || self.year >= government.next_election => {
+                    government.next_election = self.year + self.config.government.term_years;
+                    self.elect(rng)
+                }
+                Office::Council => {
+                    self.fill_council(&mut government, rng);
+                    self.eldest(&government.council)
+                }
+                _ => None,
+            };
+            if successor.is_some() && !successor.eq(&government.ruler) {
+                self.succeed(&mut government, successor.unwrap());
+            }
+
+            if government.office.eq(&Office::Monarch) && government.ruler.is_some() {
+                government.heir = self
+                    .line_of_succession(&government.ruler.unwrap())
+                    .first()
+                    .cloned();
+            }
+            self.government = Some(government);
+            self.add_timestamp("update_government");
+        }
+
+        fn seat_government(
+            self: &mut Self,
+            government: &mut Government,
+            dict: &Dictionary,
+            rng: &mut StdRng,
+        ) {
+            if government.seat.is_some()
+                && self.institutions.contains_key(&government.seat.unwrap())
+            {
+                return;
+            }
+            government.seat = self
+                .institutions
+                .values()
+                .find(|i| i.institution_type.eq(&InsitutionType::Hall))
+                .map(|i| i.id.clone());
+            if government.seat.is_none() && self.can_found(&InsitutionType::Hall) {
+                let hall = generate_institution(dict, &self, InsitutionType::Hall, rng);
+                let hall_id = hall.id.clone();
+                self.record_event(Event::InstitutionFounded {
+                    year: self.year,
+                    institution: hall_id.clone(),
+                    name: hall.name.clone(),
+                });
+                self.institutions.insert(hall_id.clone(), hall);
+                self.settle_institution(dict, &hall_id, rng);
+                government.seat = Some(hall_id);
+            }
+        }
+
+        fn succeed(self: &mut Self, government: &mut Government, successor: MindId) {
+            let predecessor = government.ruler.take();
+            let reign = government.reigns.last_mut();
+            if reign.is_some() && reign.as_ref().unwrap().ended.is_none() {
+                reign.unwrap().ended = Some(self.year);
+            }
+            let title = government
+                .ruler_title
+                .for_gender(&self.population.get(&successor).unwrap().gender)
+                .clone();
+            government.ruler = Some(successor.clone());
+            government.reigns.push(Reign {
+                ruler: successor.clone(),
+                title: title.clone(),
+                began: self.year,
+                ended: None,
+            });
+            self.record_event(Event::Succession {
+                year: self.year,
+                ruler: successor,
+                predecessor,
+                title,
+            });
+        }
+
+        // a monarch's living children eldest first, then their grandchildren
+        pub fn line_of_succession(self: &Self, ruler: &MindId) -> Vec<MindId> {
+            let mind = self.population.get(ruler).unwrap();
+            let mut line: Vec<MindId> = Vec::new();
+            for verb in [RelationVerb::Child, RelationVerb::Grandchild] {
+                let mut descendants: Vec<MindId> = mind
+                    .get_relations(verb)
+                    .into_iter()
+                    .filter(|id| {
+                        let m = self.population.get(id);
+                        return m.is_some() && m.unwrap().alive;
+                    })
+                    .collect();
+                descendants.sort_by_key(|id| {
+                    let m = self.population.get(id).unwrap();
+                    return (m.year_of_birth, m.id.clone());
+                });
+                line.extend(descendants);
+            }
+            return line;
+        }
+
+        // a line that dies out passes to the longest serving official of the hall, or
+        // failing that any adult who can take it
+        fn new_line(self: &Self, government: &Government, rng: &mut StdRng) -> Option<MindId> {
+            let hall = government.seat.and_then(|id| self.institutions.get(&id));
+            if hall.is_some() {
+                let official = hall
+                    .unwrap()
+                    .staff
+                    .values()
+                    .filter(|s| self.population.get(&s.employee_id).unwrap().alive)
+                    .min_by_key(|s| (s.started_year, s.employee_id.clone()));
+                if official.is_some() {
+                    return Some(official.unwrap().employee_id.clone());
+                }
+            }
+            return self.adults().choose(rng).cloned();
+        }
+
+        // the most outgoing of a handful of candidates wins
+        fn elect(self: &Self, rng: &mut StdRng) -> Option<MindId> {
+            let mut adults = self.adults();
+            adults.shuffle(rng);
+            return adults
+                .into_iter()
+                .take(self.config.government.candidates)
+                .fold(None, |winner: Option<MindId>, candidate| {
+                    let extraversion = |id: &MindId| {
+                        self.population
+                            .get(id)
+                            .unwrap()
+                            .personality
+                            .matrix
+                            .extraversion
+                    };
+                    if winner.is_none() || extraversion(&candidate) > extraversion(&winner.unwrap())
+                    {
+                        return Some(candidate);
+                    }
+                    return winner;
+                });
+        }
+
+        fn fill_council(self: &Self, government: &mut Government, rng: &mut StdRng) {
+            government
+                .council
+                .retain(|id| self.population.get(id).unwrap().alive);
+            let mut adults: Vec<MindId> = self
+                .adults()
+                .into_iter()
+                .filter(|id| !government.council.contains(id))
+                .collect();
+            adults.shuffle(rng);
+            let vacancies = self
+                .config
+                .government
+                .council_size
+                .saturating_sub(government.council.len());
+            government
+                .council
+                .extend(adults.into_iter().take(vacancies));
+        }
+
+        fn eldest(self: &Self, minds: &BTreeSet<MindId>) -> Option<MindId> {
+            return minds
+                .iter()
+                .min_by_key(|id| self.population.get(id).unwrap().year_of_birth)
+                .cloned();
+        }
+
+        fn adults(self: &Self) -> Vec<MindId> {
+            return self
+                .population
+                .values()
+                .filter(|m| m.alive && m.age >= self.culture_by_id(&m.culture).adult_age)
+                .map(|m| m.id.clone())
+                .collect();
+        }
+
+        // rulers, their heirs and families, and councillors are known by their titles
+        pub fn title_of(self: &Self, id: &MindId) -> Option<String> {
+            let mind = self.population.get(id);
+            if self.government.is_none() || mind.is_none() || !mind.unwrap().alive {
+                return None;
+            }
+            let government = self.government.as_ref().unwrap();
+            let gender = &mind.unwrap().gender;
+            if government.ruler.eq(&Some(id.clone())) {
+                return Some(government.ruler_title.for_gender(gender).clone());
+            }
+            if government.heir.eq(&Some(id.clone())) && government.heir_title.is_some() {
+                return Some(
+                    government
+                        .heir_title
+                        .as_ref()
+                        .unwrap()
+                        .for_gender(gender)
+                        .clone(),
+                );
+            }
+            if government.honoured_title.is_none() {
+                return None;
+            }
+            let honoured = match government.office {
+                Office::Monarch => {
+                    government.ruler.is_some() && {
+                        let ruler = self.population.get(&government.ruler.unwrap()).unwrap();
+                        ruler.get_relations(RelationVerb::Spouse).contains(id)
+                            || ruler.get_relations(RelationVerb::Child).contains(id)
+                    }
+                }
+                Office::Council => government.council.contains(id),
+                Office::Mayor => false,
+            };
+            if honoured {
+                return Some(
+                    government
+                        .honoured_title
+                        .as_ref()
+                        .unwrap()
+                        .for_gender(gender)
+                        .clone(),
+                );
+            }
+            return None;
+        }
+
+        pub fn titled_name(self: &Self, id: &MindId) -> String {
+            let title = self.title_of(id);
+            if title.is_some() {
+                return format!("{} {}", title.unwrap(), self.mind_name(id));
+            }
+            return self.mind_name(id);
+        }
+
+        pub fn reigns_of(self: &Self, id: &MindId) -> Vec<&Reign> {
+            if self.government.is_none() {
+                return Vec::new();
+            }
+            return self
+                .government
+                .as_ref()
+                .unwrap()
+                .reigns
+                .iter()
+                .filter(|r| r.ruler.eq(id))
+                .collect();
+        }
+
+        pub fn export_government(self: &Self) -> String {
+            let mut output = String::new();
+            if self.government.is_none() {
+                return output;
+            }
+            let government = self.government.as_ref().unwrap();
+            output += &format!("Office: {}  \n", government.office);
+            if government.seat.is_some() {
+                output += &format!(
+                    "Seat: {}  \n",
+                    self.institution_name(&government.seat.unwrap())
+                );
+            }
+            if government.ruler.is_some() {
+                output += &format!(
+                    "Ruler: {}  \n",
+                    self.titled_name(&government.ruler.unwrap())
+                );
+            }
+            if government.office.eq(&Office::Monarch) && government.ruler.is_some() {
+                let line = self.line_of_succession(&government.ruler.unwrap());
+                if line.len() > 0 {
+                    output += "Line of succession:  \n";
+                    for id in line.iter().take(5) {
+                        output += &format!(" - {}  \n", self.titled_name(id));
+                    }
+                }
+            }
+            if government.office.eq(&Office::Mayor) {
+                output += &format!("Next election: {}  \n", government.next_election);
+            }
+            if government.council.len() > 0 {
+                output += "Council:  \n";
+                for id in &government.council {
+                    output += &format!(" - {}  \n", self.titled_name(id));
+                }
+            }
+            if government.reigns.len() > 0 {
+                output += "\nRulers:  \n";
+                for reign in &government.reigns {
+                    output += &format!(
+                        " - {} {}, {} to {}  \n",
+                        reign.title,
+                        self.mind_name(&reign.ruler),
+                        reign.began,
+                        if reign.ended.is_some() {
+                            reign.ended.unwrap().to_string()
+                        } else {
+                            String::from("now")
+                        }
+                    );
+                }
+            }
+            return output;
+        }
+    }
+
+    #[test]
+    fn test_succession() {
+        use crate::city::{city::random_city, config::config::SimulationConfig};
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            100,
+            5,
+        );
+        for _i in 0..60 {
+            city.simulate_year(&dict);
+        }
+        let government = city.government.as_ref().unwrap();
+        assert_eq!(government.office, Office::Monarch);
+        let seat = city.institutions.get(&government.seat.unwrap()).unwrap();
+        assert_eq!(seat.institution_type, InsitutionType::Hall);
+        // every reign but the current one has ended, each recorded as a succession
+        assert!(government.reigns.len() > 1);
+        for (i, reign) in government.reigns.iter().enumerate() {
+            assert_eq!(reign.ended.is_none(), i == government.reigns.len() - 1);
+        }
+        let successions = city
+            .chronicle
+            .events()
+            .iter()
+            .filter(|e| matches!(e, Event::Succession { .. }))
+            .count();
+        assert_eq!(successions, government.reigns.len());
+        let ruler = city.population.get(&government.ruler.unwrap()).unwrap();
+        assert!(ruler.alive);
+        if government.heir.is_some() {
+            assert!(ruler
+                .get_relations(RelationVerb::Child)
+                .union(&ruler.get_relations(RelationVerb::Grandchild))
+                .any(|id| id.eq(&government.heir.unwrap())));
+        }
+        let title = government.ruler_title.for_gender(&ruler.gender);
+        assert!(ruler.print(&city).contains(title));
+        assert!(city.export_government().contains(title));
+        let titled = format!("{} {}", title, city.mind_name(&ruler.id));
+        assert!(ruler
+            .biography(&city)
+            .starts_with(&format!("### {}", titled)));
+        assert!(city
+            .describe_life(&ruler.id)
+            .starts_with(&format!("## {}", titled)));
+        assert!(city.export_chronicle().contains(&titled));
+
+        // the monarchy's theme is one the culture's historical figures held
+        let themes: Vec<&str> = ["Royal", "Duchal"]
+            .into_iter()
+            .filter(|theme| {
+                city.culture
+                    .historical_names
+                    .iter()
+                    .any(|(t, _, _)| t.tags.contains(*theme))
+            })
+            .collect();
+        let ruler_word = dict
+            .words
+            .values()
+            .find(|w| w.base.eq(&government.ruler_title.male) && w.tags.contains("Ruler"))
+            .unwrap();
+        assert!(themes.len() == 0 || themes.iter().any(|t| ruler_word.tags.contains(*t)));
+    }
+}
@@ -0,0 +1,374 @@
+pub mod house {
+    use std::collections::BTreeSet;
+
+    use procgen_templater::dictionary::{dictionary::Dictionary, word::word::WordType};
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use crate::{
+        city::{
+            area::area::AreaId,
+            chronicle::chronicle::Event,
+            city::City,
+            government::government::Office,
+            population::mind::{mind::MindId, relations::relations::RelationVerb},
+        },
+        seeded::seeded::{random_id, random_word, random_word_without},
+    };
+
+    pub type HouseId = Uuid;
+
+    // A noble family line. Members are everyone who has belonged to the house, the dead
+    // included, so the house keeps its history after its members are gone.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct House {
+        pub id: HouseId,
+        pub name: String,
+        pub founder: MindId,
+        pub head: MindId,
+        pub members: BTreeSet<MindId>,
+        // members by marriage, who don't pass the house on to their children
+        pub married_in: BTreeSet<MindId>,
+        pub titles: BTreeSet<String>,
+        // the district the head lives in
+        pub seat: Option<AreaId>,
+        pub heraldry: String,
+        pub founded: usize,
+        pub extinct: Option<usize>,
+    }
+
+    impl House {
+        pub fn print(self: &Self, city: &City) -> String {
+            let mut output = String::new();
+            output += &format!("### {}  \n", self.name);
+            output += &format!("Arms: {}  \n", self.heraldry);
+            output += &format!(
+                "Founded: {} by {}  \n",
+                self.founded,
+                city.mind_name(&self.founder)
+            );
+            if self.extinct.is_some() {
+                output += &format!("Died out: {}  \n", self.extinct.unwrap());
+            } else {
+                output += &format!("Head: {}  \n", city.titled_name(&self.head));
+            }
+            if self.seat.is_some() {
+                output += &format!("Seat: {}  \n", city.area_name(&self.seat.unwrap()));
+            }
+            if self.titles.len() > 0 {
+                let titles: Vec<&str> = self.titles.iter().map(|t| t.as_str()).collect();
+                output += &format!("Titles held: {}  \n", titles.join(", "));
+            }
+            let living = city.living_members(self);
+            output += &format!(
+                "Members: {} living of {}  \n",
+                living.len(),
+                self.members.len()
+            );
+            for id in living {
+                let mind = city.population.get(&id).unwrap();
+                output += &format!("    {}, {}  \n", city.titled_name(&id), mind.age);
+            }
+            return output;
+        }
+    }
+
+    // a creature in one colour on a field of another
+    fn random_heraldry(dict: &Dictionary, rng: &mut StdRng) -> String {
+        let colour = |rng: &mut StdRng| {
+            return random_word(
+                dict,
+                (
+                    WordType::Adjective,
+                    vec![vec![String::from("Colour"), String::from("PrimaryColour")]],
+                ),
+                rng,
+            )
+            .unwrap()
+            .base
+            .clone();
+        };
+        let field = colour(rng);
+        let mut charge = colour(rng);
+        for _i in 0..5 {
+            if !charge.eq(&field) {
+                break;
+            }
+            charge = colour(rng);
+        }
+        let creature = random_word_without(
+            dict,
+            (
+                WordType::Noun,
+                vec![vec![
+                    String::from("Mammal"),
+                    String::from("Bird"),
+                    String::from("Reptile"),
+                    String::from("Mythical"),
+                ]],
+            ),
+            vec![String::from("Sentient")],
+            rng,
+        )
+        .unwrap()
+        .base
+        .clone();
+        return format!("{} {} on a field of {}", charge, creature, field);
+    }
+
+    impl City {
+        pub fn house_of(self: &Self, mind_id: &MindId) -> Option<&House> {
+            let mind = self.population.get(mind_id);
+            if mind.is_none() || mind.unwrap().house.is_none() {
+                return None;
+            }
+            return self.houses.get(&mind.unwrap().house.unwrap());
+        }
+
+        pub fn living_members(self: &Self, house: &House) -> Vec<MindId> {
+            return house
+                .members
+                .iter()
+                .filter(|id| self.population.get(id).unwrap().alive)
+                .cloned()
+                .collect();
+        }
+
+        fn found_house(self: &mut Self, dict: &Dictionary, founder: &MindId, rng: &mut StdRng) {
+            let mind = self.population.get(founder).unwrap();
+            let house = House {
+                id: random_id(rng),
+                name: format!("House {}", mind.last_name),
+                founder: founder.clone(),
+                head: founder.clone(),
+                members: BTreeSet::from([founder.clone()]),
+                married_in: BTreeSet::new(),
+                titles: BTreeSet::new(),
+                seat: mind.area.clone(),
+                heraldry: random_heraldry(dict, rng),
+                founded: self.year,
+                extinct: None,
+            };
+            self.population.get_mut(founder).unwrap().house = Some(house.id.clone());
+            self.record_event(Event::HouseFounded {
+                year: self.year,
+                house: house.id.clone(),
+                name: house.name.clone(),
+                founder: founder.clone(),
+            });
+            self.houses.insert(house.id.clone(), house);
+        }
+
+        fn join_house(self: &mut Self, mind_id: &MindId, house_id: &HouseId, by_marriage: bool) {
+            self.population.get_mut(mind_id).unwrap().house = Some(house_id.clone());
+            let house = self.houses.get_mut(house_id).unwrap();
+            house.members.insert(mind_id.clone());
+            if by_marriage {
+                house.married_in.insert(mind_id.clone());
+            }
+        }
+
+        // Children are born into the house of the parent they take their name from, the
+        // older house when the name comes from both, as long as that parent was born into it.
+        // Those without a house marry into their spouse's. A house that has died out takes no
+        // one in.
+        fn house_to_join(self: &Self, mind_id: &MindId) -> Option<(HouseId, bool)> {
+            let mind = self.population.get(mind_id).unwrap();
+            let mut parent_houses: Vec<&House> = mind
+                .get_relations(RelationVerb::Parent)
+                .iter()
+                .filter(|p| {
+                    let parent = self.population.get(p).unwrap();
+                    return mind.last_name.contains(&parent.first_name)
+                        || parent
+                            .last_name
+                            .split('-')
+                            .any(|name| mind.last_name.contains(name));
+                })
+                .filter_map(|p| {
+                    self.house_of(p)
+                        .filter(|h| h.extinct.is_none() && !h.married_in.contains(p))
+                })
+                .collect();
+            parent_houses.sort_by_key(|h| (h.founded, h.id.clone()));
+            if parent_houses.len() > 0 {
+                return Some((parent_houses[0].id.clone(), false));
+            }
+            return mind
+                .get_relations(RelationVerb::Spouse)
+                .iter()
+                .find_map(|s| self.house_of(s).filter(|h| h.extinct.is_none()))
+                .map(|h| (h.id.clone(), true));
+        }
+
+        // The year's changes to the noble houses. A monarch without a house founds one and
+        // while there are fewer than max_houses a married citizen may be ennobled. New
+        // children and spouses join their family's house, and a house whose head has died or
+        // left passes to the head's eldest descendant, or its eldest member, until none are left.
+        pub fn update_houses(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            let ruler = self
+                .government
+                .as_ref()
+                .filter(|g| g.office.eq(&Office::Monarch))
+                .and_then(|g| g.ruler);
+            if ruler.is_some()
+                && self.population.get(&ruler.unwrap()).unwrap().alive
+                && self.house_of(&ruler.unwrap()).is_none()
+            {
+                self.found_house(dict, &ruler.unwrap(), rng);
+            }
+            let standing = self.houses.values().filter(|h| h.extinct.is_none()).count();
+            if standing < self.config.houses.max_houses
+                && rng.gen::<f32>() < self.config.houses.ennoble_chance
+            {
+                let candidates: Vec<MindId> = self
+                    .population
+                    .values()
+                    .filter(|m| {
+                        m.alive
+                            && m.house.is_none()
+                            && m.get_relations(RelationVerb::Spouse).len() > 0
+                    })
+                    .map(|m| m.id.clone())
+                    .collect();
+                let chosen = candidates.choose(rng).cloned();
+                if chosen.is_some() {
+                    self.found_house(dict, &chosen.unwrap(), rng);
+                }
+            }
+
+            // eldest first, so children join houses their parents joined this year
+            let mut houseless: Vec<(i32, MindId)> = self
+                .population
+                .values()
+                .filter(|m| m.alive && m.house.is_none())
+                .map(|m| (m.year_of_birth, m.id.clone()))
+                .collect();
+            houseless.sort();
+            for (_, id) in houseless {
+                let house = self.house_to_join(&id);
+                if house.is_some() {
+                    let (house_id, by_marriage) = house.unwrap();
+                    self.join_house(&id, &house_id, by_marriage);
+                }
+            }
+
+            let house_ids: Vec<HouseId> = self.houses.keys().cloned().collect();
+            for house_id in house_ids {
+                let house = self.houses.get(&house_id).unwrap();
+                if house.extinct.is_some() {
+                    continue;
+                }
+                let living = self.living_members(house);
+                let mut head = house.head.clone();
+                if !self.population.get(&head).unwrap().alive {
+                    let descendant = self
+                        .line_of_succession(&head)
+                        .into_iter()
+                        .find(|id| living.contains(id));
+                    let eldest = living
+                        .iter()
+                        .min_by_key(|id| (self.population.get(id).unwrap().year_of_birth, *id))
+                        .cloned();
+                    head = descendant.or(eldest).unwrap_or(head);
+                }
+                let titles: Vec<String> =
+                    living.iter().filter_map(|id| self.title_of(id)).collect();
+                let seat = self.population.get(&head).unwrap().area.clone();
+                let year = self.year;
+                let house = self.houses.get_mut(&house_id).unwrap();
+                if living.len() == 0 {
+                    house.extinct = Some(year);
+                    continue;
+                }
+                house.head = head;
+                house.titles.extend(titles);
+                if seat.is_some() {
+                    house.seat = seat;
+                }
+            }
+            self.add_timestamp("update_houses");
+        }
+
+        pub fn export_houses(self: &Self) -> String {
+            let mut houses: Vec<&House> = self.houses.values().collect();
+            houses.sort_by_key(|h| (h.founded, h.id.clone()));
+            let mut output = String::new();
+            for house in houses {
+                output += &house.print(&self);
+            }
+            return output;
+        }
+    }
+
+    #[test]
+    fn test_houses_follow_families() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut config = SimulationConfig::default();
+        config.houses.ennoble_chance = 0.5;
+        let mut city = random_city(&dict, &templates, &config, Era::Medieval, 100, 5);
+        for _i in 0..40 {
+            city.simulate_year(&dict);
+        }
+        let ruler = city.government.as_ref().unwrap().ruler.unwrap();
+        let ruling_house = city.house_of(&ruler).unwrap();
+        assert!(ruling_house.titles.len() > 0);
+        assert!(city.houses.len() > 1);
+        for house in city.houses.values() {
+            assert!(house.members.contains(&house.founder));
+            assert!(house.members.contains(&house.head));
+            assert_eq!(
+                house.extinct.is_none(),
+                city.living_members(house).len() > 0
+            );
+            for id in &house.members {
+                let mind = city.population.get(id).unwrap();
+                assert_eq!(mind.house, Some(house.id.clone()));
+                // children named for a member born into the house are born into a house too
+                if house.married_in.contains(id) {
+                    continue;
+                }
+                for child in mind.get_relations(RelationVerb::Child) {
+                    let child = city.population.get(&child).unwrap();
+                    if child.alive && child.last_name.eq(&mind.last_name) {
+                        assert!(child.house.is_some());
+                    }
+                }
+            }
+            assert!(city.export_houses().contains(&house.heraldry));
+        }
+
+        // a child left without a house isn't taken into their parent's once it has died out
+        let (house_id, child) = city
+            .houses
+            .values()
+            .find_map(|h| {
+                h.members
+                    .iter()
+                    .filter(|id| !h.married_in.contains(id))
+                    .flat_map(|id| {
+                        city.population
+                            .get(id)
+                            .unwrap()
+                            .get_relations(RelationVerb::Child)
+                    })
+                    .find(|c| h.members.contains(c) && city.population.get(c).unwrap().alive)
+                    .map(|c| (h.id.clone(), c))
+            })
+            .unwrap();
+        assert_eq!(city.house_to_join(&child).map(|h| h.0), Some(house_id));
+        city.population.get_mut(&child).unwrap().house = None;
+        let house = city.houses.get_mut(&house_id).unwrap();
+        house.members.remove(&child);
+        house.extinct = Some(city.year);
+        assert_ne!(city.house_to_join(&child).map(|h| h.0), Some(house_id));
+    }
+}
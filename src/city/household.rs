@@ -0,0 +1,394 @@
+pub mod household {
+    use std::collections::BTreeSet;
+    use std::fmt;
+
+    use procgen_templater::dictionary::dictionary::Dictionary;
+    use rand::{rngs::StdRng, Rng};
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use crate::{
+        city::{
+            area::area::AreaId,
+            city::{City, Era},
+            population::mind::{mind::MindId, relations::relations::RelationVerb},
+        },
+        grammar::grammar::{a_or_an, render_list},
+        seeded::seeded::random_id,
+    };
+
+    pub type HouseholdId = Uuid;
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub enum Dwelling {
+        Lodgings,
+        Cottage,
+        Townhouse,
+        Apartment,
+        House,
+    }
+
+    impl fmt::Display for Dwelling {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Dwelling::Lodgings => write!(f, "Lodgings"),
+                Dwelling::Cottage => write!(f, "Cottage"),
+                Dwelling::Townhouse => write!(f, "Townhouse"),
+                Dwelling::Apartment => write!(f, "Apartment"),
+                Dwelling::House => write!(f, "House"),
+            }
+        }
+    }
+
+    // the dead are dropped from members but keep the household they last lived in
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Household {
+        pub id: HouseholdId,
+        pub head: MindId,
+        pub members: BTreeSet<MindId>,
+        pub dwelling: Dwelling,
+        pub area: AreaId,
+    }
+
+    impl Household {
+        pub fn print(self: &Self, city: &City) -> String {
+            let mut output = String::new();
+            output += &format!(
+                "#### {} of {}  \n",
+                self.dwelling,
+                city.mind_name(&self.head)
+            );
+            for m_id in &self.members {
+                let mind = city.population.get(m_id).unwrap();
+                output += &format!(
+                    "    {} {}, {}{}  \n",
+                    mind.first_name,
+                    mind.last_name,
+                    mind.age,
+                    if m_id.eq(&self.head) { " (head)" } else { "" }
+                );
+            }
+            return output;
+        }
+    }
+
+    fn random_dwelling(era: &Era, members: usize, rng: &mut StdRng) -> Dwelling {
+        let roll = rng.gen::<f32>();
+        return match era {
+            Era::Modern => {
+                if roll < if members > 1 { 0.4 } else { 0.7 } {
+                    Dwelling::Apartment
+                } else {
+                    Dwelling::House
+                }
+            }
+            Era::Medieval | Era::Fantasy => {
+                if members == 1 && roll < 0.5 {
+                    Dwelling::Lodgings
+                } else if roll < 0.8 {
+                    Dwelling::Cottage
+                } else {
+                    Dwelling::Townhouse
+                }
+            }
+        };
+    }
+
+    impl City {
+        pub fn household_of(self: &Self, mind_id: &MindId) -> Option<&Household> {
+            let mind = self.population.get(mind_id);
+            if mind.is_none() || !mind.unwrap().alive || mind.unwrap().household.is_none() {
+                return None;
+            }
+            return self.households.get(&mind.unwrap().household.unwrap());
+        }
+
+        // the eldest member runs the household when the head dies or moves out
+        fn eldest_member(self: &Self, members: &BTreeSet<MindId>) -> Option<MindId> {
+            return members
+                .iter()
+                .max_by_key(|id| self.population.get(id).unwrap().age)
+                .cloned();
+        }
+
+        pub fn remove_from_household(self: &mut Self, mind_id: &MindId) {
+            let household_id = self.population.get(mind_id).unwrap().household;
+            if household_id.is_none() || !self.households.contains_key(&household_id.unwrap()) {
+                return;
+            }
+            let household_id = household_id.unwrap();
+            let household = self.households.get_mut(&household_id).unwrap();
+            household.members.remove(mind_id);
+            if household.members.len() == 0 {
+                self.households.remove(&household_id);
+            } else if household.head.eq(mind_id) {
+                let members = household.members.clone();
+                let head = self.eldest_member(&members).unwrap();
+                self.households.get_mut(&household_id).unwrap().head = head;
+            }
+        }
+
+        // moves every member into the area, finding a district with room for all of them
+        // when the household's own area is too full
+        fn house_members(
+            self: &mut Self,
+            dict: &Dictionary,
+            household_id: &HouseholdId,
+            rng: &mut StdRng,
+        ) {
+            let household = self.households.get(household_id).unwrap();
+            let arriving = household
+                .members
+                .iter()
+                .filter(|id| self.population.get(id).unwrap().area != Some(household.area))
+                .count();
+            let area = self.areas.get(&household.area);
+            let has_room = area.is_some()
+                && area.unwrap().occupied(&self.config.areas) + arriving <= area.unwrap().size;
+            let area_id = if has_room {
+                household.area.clone()
+            } else {
+                let size = household.members.len();
+                self.find_area_with_room(dict, size, rng)
+            };
+            let household = self.households.get_mut(household_id).unwrap();
+            household.area = area_id.clone();
+            let members = household.members.clone();
+            for m_id in members {
+                self.move_mind_to_area(&m_id, &area_id);
+            }
+        }
+
+        // a new household headed by the first mind, in the first of the members' districts
+        // that can fit everyone
+        pub fn found_household(
+            self: &mut Self,
+            dict: &Dictionary,
+            members: Vec<MindId>,
+            rng: &mut StdRng,
+        ) -> HouseholdId {
+            for m_id in &members {
+                self.remove_from_household(m_id);
+            }
+            let member_set: BTreeSet<MindId> = members.iter().cloned().collect();
+            let areas: Vec<AreaId> = members
+                .iter()
+                .filter_map(|id| self.population.get(id).unwrap().area)
+                .collect();
+            let area = areas.into_iter().find(|area_id| {
+                let arriving = members
+                    .iter()
+                    .filter(|id| self.population.get(id).unwrap().area != Some(*area_id))
+                    .count();
+                let area = self.areas.get(area_id);
+                return area.is_some()
+                    && area.unwrap().occupied(&self.config.areas) + arriving <= area.unwrap().size;
+            });
+            let area = if area.is_some() {
+                area.unwrap()
+            } else {
+                self.find_area_with_room(dict, members.len(), rng)
+            };
+            let household = Household {
+                id: random_id(rng),
+                head: members.first().unwrap().clone(),
+                members: member_set,
+                dwelling: random_dwelling(&self.culture.era, members.len(), rng),
+                area,
+            };
+            let id = household.id.clone();
+            self.households.insert(id.clone(), household);
+            for m_id in &members {
+                self.population.get_mut(m_id).unwrap().household = Some(id.clone());
+            }
+            self.house_members(dict, &id, rng);
+            return id;
+        }
+
+        fn join_household(
+            self: &mut Self,
+            dict: &Dictionary,
+            mind_id: &MindId,
+            household_id: &HouseholdId,
+            rng: &mut StdRng,
+        ) {
+            self.remove_from_household(mind_id);
+            self.households
+                .get_mut(household_id)
+                .unwrap()
+                .members
+                .insert(mind_id.clone());
+            self.population.get_mut(mind_id).unwrap().household = Some(household_id.clone());
+            self.house_members(dict, household_id, rng);
+        }
+
+        // the minor children of a mind who live under the same roof
+        fn dependants(self: &Self, mind_id: &MindId) -> Vec<MindId> {
+            let mind = self.population.get(mind_id).unwrap();
+            return mind
+                .get_relations(RelationVerb::Child)
+                .into_iter()
+                .filter(|c_id| {
+                    let child = self.population.get(c_id).unwrap();
+                    return child.alive
+                        && child.age < self.culture_by_id(&child.culture).adult_age
+                        && child.household.is_some()
+                        && child.household.eq(&mind.household);
+                })
+                .collect();
+        }
+
+        pub fn update_households(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            // the dead move out
+            let dead: Vec<MindId> = self
+                .households
+                .values()
+                .flat_map(|h| h.members.iter())
+                .filter(|id| !self.population.get(id).unwrap().alive)
+                .cloned()
+                .collect();
+            for m_id in dead {
+                self.remove_from_household(&m_id);
+            }
+
+            let citizens = self.current_citizens();
+            for m_id in &citizens {
+                let mind = self.population.get(m_id).unwrap();
+                let household = self.household_of(m_id).cloned();
+
+                // couples move in together with their children, ex-partners and grown
+                // children move out on their own
+                let partner = mind
+                    .get_current_romantic_partner()
+                    .map(|(id, _)| id.clone());
+                if partner.is_some() {
+                    let partner = partner.unwrap();
+                    let together = household.is_some()
+                        && household.as_ref().unwrap().members.contains(&partner);
+                    if !together && self.population.get(&partner).unwrap().alive {
+                        let mut members = vec![m_id.clone(), partner.clone()];
+                        for child in [self.dependants(m_id), self.dependants(&partner)].concat() {
+                            if !members.contains(&child) {
+                                members.push(child);
+                            }
+                        }
+                        self.found_household(dict, members, rng);
+                    }
+                } else if household.is_some() {
+                    let household = household.unwrap();
+                    let head = self.population.get(&household.head).unwrap();
+                    let is_ex = [RelationVerb::ExPartner, RelationVerb::ExSpouse]
+                        .iter()
+                        .any(|verb| head.get_relations(verb.clone()).contains(m_id));
+                    let grown = mind.age >= self.culture_by_id(&mind.culture).adult_age;
+                    if !household.head.eq(m_id) && (is_ex || grown) {
+                        self.found_household(dict, vec![m_id.clone()], rng);
+                    }
+                } else {
+                    // newborns join a parent, anyone else without a home finds their own
+                    let parent_household = mind
+                        .get_relations(RelationVerb::Parent)
+                        .iter()
+                        .find_map(|p_id| self.household_of(p_id).map(|h| h.id.clone()));
+                    if parent_household.is_some()
+                        && mind.age < self.culture_by_id(&mind.culture).adult_age
+                    {
+                        self.join_household(dict, m_id, &parent_household.unwrap(), rng);
+                    } else {
+                        self.found_household(dict, vec![m_id.clone()], rng);
+                    }
+                }
+            }
+            self.add_timestamp("update households");
+        }
+
+        pub fn export_households(self: &Self) -> String {
+            let mut output = String::new();
+            for area in self.areas.values() {
+                let households: Vec<&Household> = self
+                    .households
+                    .values()
+                    .filter(|h| h.area.eq(&area.id))
+                    .collect();
+                if households.len() > 0 {
+                    output += &format!("### {}  \n", area.name);
+                    for household in households {
+                        output += &household.print(&self);
+                    }
+                }
+            }
+            return output;
+        }
+
+        pub fn describe_home(self: &Self, mind_id: &MindId) -> Option<String> {
+            let household = self.household_of(mind_id);
+            if household.is_none() {
+                return None;
+            }
+            let household = household.unwrap();
+            let dwelling = household.dwelling.to_string().to_ascii_lowercase();
+            let others: Vec<String> = household
+                .members
+                .iter()
+                .filter(|id| !id.eq(&mind_id))
+                .map(|id| self.mind_name(id))
+                .collect();
+            let mut output = format!(
+                "They live in {} {} in {}",
+                a_or_an(&dwelling),
+                dwelling,
+                self.area_name(&household.area)
+            );
+            if others.len() > 0 {
+                output += &format!(
+                    " with {}",
+                    render_list(others.iter().map(|o| o.as_str()).collect())
+                );
+            }
+            return Some(output);
+        }
+    }
+
+    #[test]
+    fn test_households_follow_relations() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            120,
+            5,
+        );
+        for _i in 0..50 {
+            city.simulate_year(&dict);
+        }
+        for id in city.current_citizens() {
+            let mind = city.population.get(&id).unwrap();
+            let household = city.household_of(&id).unwrap();
+            assert!(household.members.contains(&id));
+            assert_eq!(mind.area, Some(household.area));
+            if !household.head.eq(&id) {
+                let head = city.population.get(&household.head).unwrap();
+                let lives_with_partner = [RelationVerb::Partner, RelationVerb::Spouse]
+                    .iter()
+                    .any(|verb| head.get_relations(verb.clone()).contains(&id));
+                assert!(lives_with_partner || mind.age <= city.culture_of(&mind.id).adult_age);
+            }
+        }
+        for household in city.households.values() {
+            assert!(household.members.contains(&household.head));
+            assert!(household
+                .members
+                .iter()
+                .all(|m| city.population.get(m).unwrap().alive));
+        }
+    }
+}
@@ -7,20 +7,26 @@ pub mod institutions {
     // D - Defenses (Police, Guards, Sherrif, etc)
     // S - Social Hubs
 
-    use std::collections::HashMap;
+    use std::{collections::BTreeMap, fmt};
 
     use procgen_templater::dictionary::dictionary::Dictionary;
-    use rand::{seq::SliceRandom, Rng};
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+    use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
-    use crate::city::{
-        city::City,
-        culture::culture::Culture,
-        dieties::dieties::Diety,
-        population::mind::mind::{Mind, MindId},
+    use crate::{
+        city::{
+            area::area::AreaId,
+            chronicle::chronicle::Event,
+            city::{City, Era},
+            demand::demand::Demand,
+            dieties::dieties::Diety,
+            population::mind::mind::MindId,
+        },
+        seeded::seeded::random_id,
     };
 
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub enum InsitutionCategory {
         Goverment,
         Underworld,
@@ -30,7 +36,7 @@ pub mod institutions {
         Social,
     }
 
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub enum InsitutionType {
         // Goverment
         Hall,
@@ -66,25 +72,29 @@ pub mod institutions {
 
     pub type InstitutionId = Uuid;
 
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct Institution {
         pub id: InstitutionId,
         pub name: String,
         pub category: InsitutionCategory,
         pub management: Vec<ManagementSpecification>,
         pub base_job_titles: Vec<String>,
-        pub staff: HashMap<Uuid, StaffDefinition>,
+        pub staff: BTreeMap<Uuid, StaffDefinition>,
         pub related_diety: Option<Uuid>,
         pub wealth: usize,
+        pub area: Option<AreaId>,
+        // takes in the city's sick and injured as patients
+        pub provides_care: bool,
+        pub institution_type: InsitutionType,
     }
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct ManagementSpecification {
         pub title: String,
         pub reportee_types: Vec<String>,
         pub min_reportees: u32,
         pub max_reportees: u32,
     }
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct StaffDefinition {
         pub title: String,
         pub employee_id: MindId,
@@ -141,10 +151,15 @@ pub mod institutions {
                     }
                 }
                 if basic_staff_count < basic_staff_max || self.staff.len().eq(&0) {
-                    // Base Employee
-                    let mut titles = self.base_job_titles.clone();
-                    titles.shuffle(&mut rand::thread_rng());
-                    return Some(titles.first().unwrap().clone());
+                    // Base Employee - fill whichever base title is least staffed
+                    let title = self
+                        .base_job_titles
+                        .iter()
+                        .min_by_key(|title| {
+                            self.staff.values().filter(|s| s.title.eq(*title)).count()
+                        })
+                        .unwrap();
+                    return Some(title.clone());
                 }
             }
             return None;
@@ -178,10 +193,21 @@ pub mod institutions {
 
             output += &format!("### {}  \n", self.name);
             output += &format!("Category: {:?}  \n", self.category);
+            output += &format!("Type: {}  \n", self.institution_type);
             if diety.is_some() {
                 output += &format!("Diety: {:?}/  \n", diety.unwrap().render_summary());
             }
             output += &format!("Wealth: {}  \n", self.wealth);
+            if self.area.is_some() {
+                output += &format!("District: {}  \n", city.area_name(&self.area.unwrap()));
+            }
+            if self.provides_care {
+                output += &format!("Patients: {}  \n", city.patients_of(&self.id).len());
+            }
+            let government = city.government.as_ref();
+            if government.is_some() && government.unwrap().seat.eq(&Some(self.id.clone())) {
+                output += &format!("Seat of the {}  \n", government.unwrap().office);
+            }
 
             output += &format!("Staff:  \n");
             for (mind_id, staff_definition) in &self.staff {
@@ -196,156 +222,229 @@ pub mod institutions {
         }
     }
 
-    pub fn random_institution(dict: &Dictionary, city: &City) -> Institution {
-        let mut rng = rand::thread_rng();
-        let roll = rng.gen::<f32>();
-        let underworld_chance = 0.2;
-        let temple_chance = 0.2;
-        if roll < underworld_chance {
-            return generate_underground(dict, &city.culture);
-        } else if roll < (underworld_chance + temple_chance) {
-            return generate_temple(dict, &city.culture);
-        } else {
-            return generate_social(dict, &city.culture);
+    impl InsitutionType {
+        pub fn all() -> Vec<InsitutionType> {
+            return vec![
+                InsitutionType::Hall,
+                InsitutionType::Hospital,
+                InsitutionType::Dock,
+                InsitutionType::School,
+                InsitutionType::Library,
+                InsitutionType::University,
+                InsitutionType::Prison,
+                InsitutionType::ShadyBar,
+                InsitutionType::Fence,
+                InsitutionType::Altar,
+                InsitutionType::Chapel,
+                InsitutionType::GeneralRetail,
+                InsitutionType::SpecialistRetail,
+                InsitutionType::Office,
+                InsitutionType::Bank,
+                InsitutionType::PoliceStation,
+                InsitutionType::Pub,
+                InsitutionType::Restaurant,
+                InsitutionType::Hotel,
+                InsitutionType::Theater,
+                InsitutionType::Casino,
+                InsitutionType::Club,
+                InsitutionType::Gym,
+                InsitutionType::TattooParlor,
+            ];
         }
-    }
 
-    pub fn generate_temple(dict: &Dictionary, culture: &Culture) -> Institution {
-        let mut rand = rand::thread_rng();
-        let template = dict
-            .get_random_template(vec![
-                vec!["Altar".to_string()],
-                vec![culture.era.to_string()],
-            ])
-            .unwrap();
-        let diety_ref = culture.dieties.clone();
-        let mut dieties: Vec<&Diety> = diety_ref.values().collect();
-        dieties.shuffle(&mut rand);
-        let diety = dieties.first().unwrap();
-        let diety_realm = &diety.realms.first().unwrap().base;
+        pub fn category(self: &Self) -> InsitutionCategory {
+            return match self {
+                InsitutionType::Hall
+                | InsitutionType::Hospital
+                | InsitutionType::Dock
+                | InsitutionType::School
+                | InsitutionType::Library
+                | InsitutionType::University
+                | InsitutionType::Prison => InsitutionCategory::Goverment,
+                InsitutionType::ShadyBar | InsitutionType::Fence => InsitutionCategory::Underworld,
+                InsitutionType::Altar | InsitutionType::Chapel => InsitutionCategory::Altar,
+                InsitutionType::GeneralRetail
+                | InsitutionType::SpecialistRetail
+                | InsitutionType::Office
+                | InsitutionType::Bank => InsitutionCategory::Resource,
+                InsitutionType::PoliceStation => InsitutionCategory::Defence,
+                InsitutionType::Pub
+                | InsitutionType::Restaurant
+                | InsitutionType::Hotel
+                | InsitutionType::Theater
+                | InsitutionType::Casino
+                | InsitutionType::Club
+                | InsitutionType::Gym
+                | InsitutionType::TattooParlor => InsitutionCategory::Social,
+            };
+        }
 
-        let output = Institution {
-            id: Uuid::new_v4(),
-            name: dict
-                .render_template(&template.id)
-                .unwrap()
-                .replace("Concept", &diety.realms.first().unwrap().base)
-                .replace("Diety", &diety.name),
-            category: InsitutionCategory::Altar,
-            management: vec![ManagementSpecification {
-                title: String::from("Minister"),
-                reportee_types: vec![String::from("Priest")],
-                min_reportees: 1,
-                max_reportees: 4,
-            }],
-            base_job_titles: vec!["Priest".to_string()],
-            staff: HashMap::new(),
-            related_diety: Some(diety.id.clone()),
-            wealth: rand.gen_range(1..3),
-        };
+        // the tags its name templates carry, any one of them will do
+        fn template_tags(self: &Self) -> Vec<String> {
+            return match self {
+                InsitutionType::Hall => vec![String::from("TownHall")],
+                InsitutionType::ShadyBar => vec![String::from("SketchyBar")],
+                InsitutionType::Theater => vec![
+                    String::from("Theater"),
+                    String::from("Music"),
+                    String::from("Cinema"),
+                ],
+                _ => vec![format!("{:?}", self)],
+            };
+        }
 
-        return output;
+        // base job titles in the era's terms, each with the manager they report to if any
+        fn staffing(self: &Self, era: &Era) -> Vec<(&'static str, Option<&'static str>)> {
+            let modern = era.eq(&Era::Modern);
+            return match self {
+                InsitutionType::Hall if modern => vec![("Clerk", Some("Chief Clerk"))],
+                InsitutionType::Hall => vec![("Scribe", Some("Steward"))],
+                InsitutionType::Hospital if modern => vec![("Nurse", Some("Doctor"))],
+                InsitutionType::Hospital => vec![("Healer", Some("Physician"))],
+                InsitutionType::Dock if modern => vec![("Docker", Some("Foreman"))],
+                InsitutionType::Dock => vec![("Stevedore", Some("Harbourmaster"))],
+                InsitutionType::School if modern => vec![("Teacher", Some("Headteacher"))],
+                InsitutionType::School => vec![("Tutor", Some("Schoolmaster"))],
+                InsitutionType::Library if modern => vec![("Librarian", Some("Head Librarian"))],
+                InsitutionType::Library => vec![("Copyist", Some("Librarian"))],
+                InsitutionType::University if modern => vec![("Lecturer", Some("Professor"))],
+                InsitutionType::University => vec![("Scholar", Some("Master"))],
+                InsitutionType::Prison if modern => vec![("Prison Officer", Some("Warden"))],
+                InsitutionType::Prison => vec![("Gaoler", Some("Warden"))],
+                InsitutionType::ShadyBar => vec![("Waiter", None)],
+                InsitutionType::Fence => vec![("Shopkeeper", None)],
+                InsitutionType::Altar => vec![("Priest", Some("Minister"))],
+                InsitutionType::Chapel => vec![("Acolyte", Some("Chaplain"))],
+                InsitutionType::GeneralRetail => vec![("Shop Assistant", Some("Shopkeeper"))],
+                InsitutionType::SpecialistRetail if modern => {
+                    vec![("Shop Assistant", Some("Manager"))]
+                }
+                InsitutionType::SpecialistRetail => vec![("Apprentice", Some("Master Craftsman"))],
+                InsitutionType::Office if modern => vec![("Clerk", Some("Manager"))],
+                InsitutionType::Office => vec![("Clerk", Some("Factor"))],
+                InsitutionType::Bank if modern => vec![("Teller", Some("Bank Manager"))],
+                InsitutionType::Bank => vec![("Clerk", Some("Moneylender"))],
+                InsitutionType::PoliceStation if modern => {
+                    vec![("Police Officer", Some("Sergeant"))]
+                }
+                InsitutionType::PoliceStation => vec![("Guard", Some("Captain"))],
+                InsitutionType::Pub | InsitutionType::Restaurant => {
+                    vec![("Cook", Some("Chef")), ("Waiter", Some("Manager"))]
+                }
+                InsitutionType::Hotel if modern => {
+                    vec![("Receptionist", Some("Manager")), ("Porter", None)]
+                }
+                InsitutionType::Hotel => vec![("Chambermaid", Some("Innkeeper"))],
+                InsitutionType::Theater => vec![("Actor", Some("Director")), ("Stagehand", None)],
+                InsitutionType::Casino if modern => vec![("Croupier", Some("Pit Boss"))],
+                InsitutionType::Casino => vec![("Dealer", Some("Proprietor"))],
+                InsitutionType::Club if modern => {
+                    vec![("Bartender", Some("Manager")), ("Bouncer", None)]
+                }
+                InsitutionType::Club => vec![("Steward", Some("Club Secretary"))],
+                InsitutionType::Gym if modern => vec![("Trainer", Some("Manager"))],
+                InsitutionType::Gym => vec![("Sparring Partner", Some("Master at Arms"))],
+                InsitutionType::TattooParlor => vec![("Tattooist", None)],
+            };
+        }
     }
 
-    pub fn generate_underground(dict: &Dictionary, culture: &Culture) -> Institution {
-        let mut rand = rand::thread_rng();
-        let template = dict
-            .get_random_template(vec![
-                vec!["SeedyBar".to_string(), "Fence".to_string()],
-                vec![culture.era.to_string()],
-            ])
-            .unwrap();
-        let is_fence = template.tags.contains("Fence");
-        let output = Institution {
-            id: Uuid::new_v4(),
-            name: dict.render_template(&template.id).unwrap(),
-            category: InsitutionCategory::Underworld,
-            management: Vec::new(),
-            base_job_titles: if is_fence {
-                vec!["Shopkeeper".to_string()]
-            } else {
-                vec!["Waiter".to_string()]
-            },
-            staff: HashMap::new(),
-            related_diety: None,
-            wealth: rand.gen_range(1..3),
-        };
-        return output;
+    impl fmt::Display for InsitutionType {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                InsitutionType::Hall => write!(f, "Town Hall"),
+                InsitutionType::ShadyBar => write!(f, "Shady Bar"),
+                InsitutionType::GeneralRetail => write!(f, "General Retail"),
+                InsitutionType::SpecialistRetail => write!(f, "Specialist Retail"),
+                InsitutionType::PoliceStation => write!(f, "Police Station"),
+                InsitutionType::TattooParlor => write!(f, "Tattoo Parlor"),
+                _ => write!(f, "{:?}", self),
+            }
+        }
     }
 
-    pub fn generate_social(dict: &Dictionary, culture: &Culture) -> Institution {
-        let mut rand = rand::thread_rng();
-        let template = dict
-            .get_random_template(vec![
-                vec!["Social".to_string()],
-                vec![culture.era.to_string()],
-            ])
-            .unwrap();
-        let mut output = Institution {
-            id: Uuid::new_v4(),
-            name: dict.render_template(&template.id).unwrap(),
-            category: InsitutionCategory::Social,
-            management: Vec::new(),
-            base_job_titles: Vec::new(),
-            staff: HashMap::new(),
-            related_diety: None,
-            wealth: rand.gen_range(1..3),
-        };
-        if template.tags.contains("Food") {
-            output.base_job_titles.push("Cook".to_string());
-            output.management.push(ManagementSpecification {
-                title: "Chef".to_string(),
-                reportee_types: vec!["Cook".to_string()],
-                min_reportees: 1,
-                max_reportees: 4,
-            });
-            output.base_job_titles.push("Waiter".to_string());
-            output.management.push(ManagementSpecification {
-                title: "Manager".to_string(),
-                reportee_types: vec!["Waiter".to_string()],
-                min_reportees: 1,
-                max_reportees: 4,
-            })
-        }
-        if template.tags.contains("Theater") {
-            output.base_job_titles.push("Actor".to_string());
-            output.management.push(ManagementSpecification {
-                title: "Stage Hand".to_string(),
-                reportee_types: vec!["Actor".to_string()],
-                min_reportees: 1,
-                max_reportees: 4,
-            });
-        }
-        if template.tags.contains("Music") {
-            output.base_job_titles.push("Waiter".to_string());
-            output.management.push(ManagementSpecification {
-                title: "Musician".to_string(),
-                reportee_types: vec!["Actor".to_string()],
-                min_reportees: 1,
-                max_reportees: 4,
-            });
+    impl City {
+        // whether there are names for the type in the city's era and room for another, docks
+        // need water and a city has a single hall
+        pub fn can_found(self: &Self, institution_type: &InsitutionType) -> bool {
+            let has_template = self.templates.has_template(vec![
+                institution_type.template_tags(),
+                self.culture.era.tags(),
+            ]);
+            return has_template
+                && match institution_type {
+                    InsitutionType::Dock => self.site.coastal || self.site.river,
+                    InsitutionType::Hall => !self
+                        .institutions
+                        .values()
+                        .any(|i| i.institution_type.eq(&InsitutionType::Hall)),
+                    _ => true,
+                };
         }
-        if template.tags.contains("Cinema") {
-            output.base_job_titles.push("Waiter".to_string());
-            output.management.push(ManagementSpecification {
-                title: "Manager".to_string(),
-                reportee_types: vec!["Shopkeeper".to_string()],
-                min_reportees: 2,
-                max_reportees: 6,
-            });
+    }
+
+    // named from the type's templates for the era and staffed as the type is, altars and
+    // chapels are dedicated to one of the culture's dieties
+    pub fn generate_institution(
+        dict: &Dictionary,
+        city: &City,
+        institution_type: InsitutionType,
+        rng: &mut StdRng,
+    ) -> Institution {
+        let culture = &city.culture;
+        let template = city
+            .templates
+            .get_random_template(
+                vec![institution_type.template_tags(), culture.era.tags()],
+                rng,
+            )
+            .unwrap();
+        let mut name = template.render(&dict, rng).unwrap();
+        let mut related_diety: Option<Uuid> = None;
+        if institution_type.category().eq(&InsitutionCategory::Altar) {
+            let mut dieties: Vec<&Diety> = culture.dieties.values().collect();
+            dieties.shuffle(rng);
+            let diety = dieties.first().unwrap();
+            let diety_realm = &diety.realms.first().unwrap().base;
+            name = name
+                .replace("Concept", diety_realm)
+                .replace("Diety", &diety.name);
+            related_diety = Some(diety.id.clone());
         }
-        return output;
+        let staffing = institution_type.staffing(&culture.era);
+        return Institution {
+            id: random_id(rng),
+            name,
+            category: institution_type.category(),
+            management: staffing
+                .iter()
+                .filter(|(_, manager)| manager.is_some())
+                .map(|(base, manager)| ManagementSpecification {
+                    title: manager.unwrap().to_string(),
+                    reportee_types: vec![base.to_string()],
+                    min_reportees: 1,
+                    max_reportees: 4,
+                })
+                .collect(),
+            base_job_titles: staffing.iter().map(|(base, _)| base.to_string()).collect(),
+            staff: BTreeMap::new(),
+            related_diety,
+            wealth: rng.gen_range(1..3),
+            area: None,
+            provides_care: institution_type.eq(&InsitutionType::Hospital),
+            institution_type,
+        };
     }
 
     impl City {
-        pub fn fire_percentage(self: &mut Self, percentage: f32) {
-            let population_clone = self.population.clone();
-            let to_fire_count = (population_clone.len() as f32 * percentage) as usize;
-            let mut ids: Vec<&Uuid> = population_clone.keys().into_iter().collect();
-            ids.shuffle(&mut rand::thread_rng());
+        pub fn fire_percentage(self: &mut Self, percentage: f32, rng: &mut StdRng) {
+            let to_fire_count = (self.population.len() as f32 * percentage) as usize;
+            let mut ids: Vec<Uuid> = self.population.keys().cloned().collect();
+            ids.shuffle(rng);
+            let mut fired: Vec<Uuid> = Vec::new();
             for id in ids.iter().take(to_fire_count) {
                 let mind = self.population.get_mut(id).unwrap();
-                if mind.employer.is_some() {
+                if mind.alive && mind.employer.is_some() {
                     let employer_id = mind.employer.unwrap();
                     let institution = self.institutions.get_mut(&employer_id).unwrap();
                     if institution.staff.len() > 1 {
@@ -353,21 +452,36 @@ pub mod institutions {
                         // get institution
                         // remove from institution employees
                         mind.employer = None;
+                        self.chronicle.record(Event::Fired {
+                            year: self.year,
+                            mind: mind.id.clone(),
+                            institution: employer_id,
+                        });
+                        fired.push(mind.id.clone());
                     }
                 }
             }
+            for id in fired {
+                self.end_role(&id);
+            }
             self.add_timestamp("fire percentage");
         }
-        pub fn fill_and_create_jobs(self: &mut Self, dict: &Dictionary) {
-            let population_ref = self.population.clone();
-            let institution_ref = self.institutions.clone();
-            let mut unemployed: Vec<&Mind> = population_ref
+        pub fn fill_and_create_jobs(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            let mut unemployed: Vec<Uuid> = self
+                .population
                 .values()
-                .filter(|m| m.employer.is_none() && m.age > self.culture.adult_age)
+                .filter(|m| {
+                    m.alive
+                        && m.employer.is_none()
+                        && m.age > self.culture_by_id(&m.culture).adult_age
+                        && m.health.can_work(&self.config.health)
+                })
+                .map(|m| m.id.clone())
                 .collect();
-            unemployed.shuffle(&mut rand::thread_rng());
+            unemployed.shuffle(rng);
 
-            let mut hiring_institutions: Vec<Uuid> = institution_ref
+            let mut hiring_institutions: Vec<Uuid> = self
+                .institutions
                 .values()
                 .filter(|i| i.next_role().is_some())
                 .map(|i| i.id)
@@ -375,9 +489,11 @@ pub mod institutions {
             println!(
                 "{}/{} Institutions Hiring",
                 hiring_institutions.len(),
-                institution_ref.len(),
+                self.institutions.len(),
             );
-            for mind in unemployed {
+            // worked out when the first institution is founded, then kept up to date
+            let mut demand: Option<Vec<Demand>> = None;
+            for mind_id in unemployed {
                 // TEMP - late keep minds in same institution type unless chance to break out happens
 
                 // find usable institution
@@ -386,39 +502,71 @@ pub mod institutions {
                 if target_key.is_some() {
                     // let institution = self.institutions.get(&target_key).unwrap();
 
-                    let mind_mut = self.population.get_mut(&mind.id).unwrap();
+                    let mind_mut = self.population.get_mut(&mind_id).unwrap();
                     mind_mut.employer = Some(target_key.unwrap().clone());
 
                     let institution_mut = self.institutions.get_mut(&target_key.unwrap()).unwrap();
+                    let title = institution_mut.next_role().unwrap();
                     institution_mut.staff.insert(
-                        mind.id.clone(),
+                        mind_id.clone(),
                         StaffDefinition {
-                            title: institution_mut.next_role().unwrap(),
-                            employee_id: mind.id.clone(),
+                            title: title.clone(),
+                            employee_id: mind_id.clone(),
                             salary: 0,
                             started_year: self.year.clone(),
                         },
                     );
+                    self.chronicle.record(Event::Hired {
+                        year: self.year,
+                        mind: mind_id.clone(),
+                        institution: institution_mut.id.clone(),
+                        title: title.clone(),
+                    });
+                    let institution_id = institution_mut.id.clone();
                     if institution_mut.next_role().is_none() {
-                        hiring_institutions.retain(|i| !i.eq(&institution_mut.id));
+                        hiring_institutions.retain(|i| !i.eq(&institution_id));
                     }
+                    self.begin_role(&mind_id, &institution_id, &title);
                 } else {
-                    // New Institution
-                    let mut new_institution = random_institution(dict, &self);
+                    // New Institution, the rest stay out of work once the city's needs are met
+                    if demand.is_none() {
+                        demand = Some(self.demand());
+                    }
+                    let new_institution =
+                        self.needed_institution(demand.as_mut().unwrap(), dict, rng);
+                    if new_institution.is_none() {
+                        break;
+                    }
+                    let mut new_institution = new_institution.unwrap();
+                    let title = new_institution.next_role().unwrap();
                     new_institution.staff.insert(
-                        mind.id.clone(),
+                        mind_id.clone(),
                         StaffDefinition {
-                            title: new_institution.next_role().unwrap(),
-                            employee_id: mind.id.clone(),
+                            title: title.clone(),
+                            employee_id: mind_id.clone(),
                             salary: 0,
                             started_year: self.year.clone(),
                         },
                     );
-                    let mind_mut = self.population.get_mut(&mind.id).unwrap();
+                    self.record_event(Event::InstitutionFounded {
+                        year: self.year,
+                        institution: new_institution.id.clone(),
+                        name: new_institution.name.clone(),
+                    });
+                    self.record_event(Event::Hired {
+                        year: self.year,
+                        mind: mind_id.clone(),
+                        institution: new_institution.id.clone(),
+                        title: title.clone(),
+                    });
+                    let mind_mut = self.population.get_mut(&mind_id).unwrap();
                     mind_mut.employer = Some(new_institution.id.clone());
                     hiring_institutions.push(new_institution.id.clone());
+                    let institution_id = new_institution.id.clone();
                     self.institutions
                         .insert(new_institution.id.clone(), new_institution);
+                    self.begin_role(&mind_id, &institution_id, &title);
+                    self.settle_institution(dict, &institution_id, rng);
                 }
             }
             self.add_timestamp("fill_and_create_jobs");
@@ -428,12 +576,66 @@ pub mod institutions {
     #[test]
     fn test_name_gen() {
         use crate::city::city::{random_city, Era};
+        use crate::city::config::config::SimulationConfig;
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
+        };
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let city = &random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            5,
+            0,
+        );
+        let mut rng = seeded_rng(0, "test", 0);
+        for institution_type in InsitutionType::all() {
+            if city.can_found(&institution_type) {
+                let inst = generate_institution(&dict, &city, institution_type, &mut rng);
+                println!("{}", inst.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_type_generates() {
+        use crate::city::city::{random_city, Era};
+        use crate::city::config::config::SimulationConfig;
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
+        };
         use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
         let dict = build_dictionary_from_folder("./data_files");
-        let city = &random_city(&dict, Era::Medieval, 5);
-        for _i in 0..50 {
-            let inst = random_institution(&dict, &city);
-            println!("{}", inst.name);
+        let templates = build_templates_from_folder("./data_files");
+        for era in [Era::Modern, Era::Medieval, Era::Fantasy] {
+            let city = random_city(
+                &dict,
+                &templates,
+                &SimulationConfig::default(),
+                era.clone(),
+                5,
+                3,
+            );
+            let mut rng = seeded_rng(3, "test", 0);
+            for institution_type in InsitutionType::all() {
+                assert!(
+                    templates.has_template(vec![institution_type.template_tags(), era.tags(),]),
+                    "{} has no {} names",
+                    institution_type,
+                    era
+                );
+                let institution =
+                    generate_institution(&dict, &city, institution_type.clone(), &mut rng);
+                assert!(
+                    !institution.name.contains("NOUN") && !institution.name.contains("Concept")
+                );
+                assert_eq!(institution.category, institution_type.category());
+                assert_eq!(institution.institution_type, institution_type);
+                assert!(institution.next_role().is_some());
+            }
         }
     }
 }
@@ -0,0 +1,503 @@
+pub mod map {
+    use std::collections::BTreeMap;
+
+    use procgen_templater::dictionary::{dictionary::Dictionary, word::word::WordType};
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+    use crate::{
+        city::{
+            area::area::AreaId,
+            city::City,
+            institutions::institutions::{InsitutionType, InstitutionId},
+        },
+        seeded::seeded::{random_word, seeded_rng},
+    };
+
+    // width of one district cell, everything else on the map is scaled from it
+    const CELL: f32 = 200.0;
+    // how far a cell corner can wander, as a fraction of the cell
+    const CORNER_JITTER: f32 = 0.18;
+    const MARGIN: f32 = 0.6 * CELL;
+    const SEA_FEATURES: [&str; 2] = ["Bay", "Harbour"];
+
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub struct Point {
+        pub x: f32,
+        pub y: f32,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct MapDistrict {
+        pub area: AreaId,
+        pub name: String,
+        pub cell: (i32, i32),
+        pub outline: Vec<Point>,
+        pub centre: Point,
+        pub coastal: bool,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct MapBuilding {
+        pub institution: InstitutionId,
+        pub name: String,
+        pub position: Point,
+        pub size: f32,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct Coast {
+        pub name: String,
+        pub label: Point,
+        pub sea: Vec<Point>,
+        pub docks: Vec<Vec<Point>>,
+    }
+
+    // Districts sit on a grid of jittered cells spiralling out from the first one laid out.
+    // Corners are jittered from their own grid position, so neighbours always share edges
+    // and founding a new district never moves the old ones. Coastal cities keep the sea to
+    // one side of the grid.
+    #[derive(PartialEq, Debug, Clone)]
+    pub struct CityMap {
+        pub min: Point,
+        pub max: Point,
+        pub districts: Vec<MapDistrict>,
+        pub roads: Vec<(Point, Point)>,
+        pub buildings: Vec<MapBuilding>,
+        pub coast: Option<Coast>,
+    }
+
+    // cells by ring around the origin, then by angle, so the city grows outwards evenly
+    fn spiral_cells(count: usize, coastal: bool) -> Vec<(i32, i32)> {
+        let mut output: Vec<(i32, i32)> = Vec::new();
+        let mut ring: i32 = 0;
+        while output.len() < count {
+            let mut cells: Vec<(i32, i32)> = Vec::new();
+            for i in -ring..=ring {
+                for j in -ring..=ring {
+                    // the sea takes every row past the first
+                    if i.abs().max(j.abs()) == ring && !(coastal && j > 0) {
+                        cells.push((i, j));
+                    }
+                }
+            }
+            cells.sort_by(|a, b| {
+                let angle_a = (a.1 as f32).atan2(a.0 as f32);
+                let angle_b = (b.1 as f32).atan2(b.0 as f32);
+                return angle_a.partial_cmp(&angle_b).unwrap();
+            });
+            output.extend(cells);
+            ring += 1;
+        }
+        output.truncate(count);
+        return output;
+    }
+
+    // corners along the coast only move sideways so the shore stays a straight line
+    fn cell_corner(seed: u64, i: i32, j: i32, coastal: bool) -> Point {
+        let mut rng = seeded_rng(seed, &format!("map corner {} {}", i, j), 0);
+        let dx = (rng.gen::<f32>() - 0.5) * 2.0 * CORNER_JITTER * CELL;
+        let dy = (rng.gen::<f32>() - 0.5) * 2.0 * CORNER_JITTER * CELL;
+        return Point {
+            x: i as f32 * CELL + dx,
+            y: j as f32 * CELL + if coastal && j == 1 { 0.0 } else { dy },
+        };
+    }
+
+    // quarter turns the whole map so the sea isn't always to the south
+    fn rotate(point: Point, turns: u8) -> Point {
+        return match turns % 4 {
+            1 => Point {
+                x: -point.y,
+                y: point.x,
+            },
+            2 => Point {
+                x: -point.x,
+                y: -point.y,
+            },
+            3 => Point {
+                x: point.y,
+                y: -point.x,
+            },
+            _ => point,
+        };
+    }
+
+    fn sea_name(dict: &Dictionary, rng: &mut StdRng) -> String {
+        let mut features: Vec<String> = SEA_FEATURES
+            .iter()
+            .filter(|f| {
+                dict.words
+                    .values()
+                    .any(|w| w.base.eq(*f) && w.tags.contains("AreaFeature"))
+            })
+            .map(|f| f.to_string())
+            .collect();
+        features.shuffle(rng);
+        let feature = features.first().cloned().unwrap_or(String::from("Sea"));
+        let adjective = random_word(
+            dict,
+            (WordType::Adjective, vec![vec!["Quality".to_string()]]),
+            rng,
+        );
+        return if adjective.is_some() {
+            format!("{} {}", adjective.unwrap().base, feature)
+        } else {
+            format!("The {}", feature)
+        };
+    }
+
+    fn escape(text: &str) -> String {
+        return text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;");
+    }
+
+    fn path(points: &Vec<Point>) -> String {
+        return points
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", p.x, p.y))
+            .collect::<Vec<String>>()
+            .join(" ");
+    }
+
+    impl City {
+        // districts in the order they were laid out, ties broken by id so a saved city
+        // always draws the same way
+        fn areas_by_founding(self: &Self) -> Vec<AreaId> {
+            let mut areas: Vec<(usize, AreaId)> = self
+                .areas
+                .values()
+                .map(|a| (a.founded_year, a.id.clone()))
+                .collect();
+            areas.sort();
+            return areas.into_iter().map(|(_year, id)| id).collect();
+        }
+
+        fn docks_in(self: &Self, area_id: &AreaId) -> usize {
+            return self
+                .areas
+                .get(area_id)
+                .unwrap()
+                .institutions
+                .iter()
+                .filter(|id| {
+                    self.institutions
+                        .get(id)
+                        .is_some_and(|i| i.institution_type.eq(&InsitutionType::Dock))
+                })
+                .count();
+        }
+
+        pub fn generate_map(self: &Self, dict: &Dictionary) -> CityMap {
+            let mut rng = seeded_rng(self.seed, "map", 0);
+            let coastal = !self.culture.landlocked;
+            let turns = rng.gen_range(0..4) as u8;
+            let area_ids = self.areas_by_founding();
+            let cells = spiral_cells(area_ids.len(), coastal);
+
+            let mut districts: Vec<MapDistrict> = Vec::new();
+            let mut buildings: Vec<MapBuilding> = Vec::new();
+            for (area_id, (i, j)) in area_ids.iter().zip(cells.iter()) {
+                let (i, j) = (*i, *j);
+                let area = self.areas.get(area_id).unwrap();
+                let outline: Vec<Point> = [(i, j), (i + 1, j), (i + 1, j + 1), (i, j + 1)]
+                    .iter()
+                    .map(|(x, y)| rotate(cell_corner(self.seed, *x, *y, coastal), turns))
+                    .collect();
+                let centre = Point {
+                    x: outline.iter().map(|p| p.x).sum::<f32>() / 4.0,
+                    y: outline.iter().map(|p| p.y).sum::<f32>() / 4.0,
+                };
+
+                // institutions on a square grid of plots inside the district
+                let plots = (area.institutions.len() as f32).sqrt().ceil().max(1.0);
+                let plot_size = CELL * 0.6 / plots;
+                for (n, institution_id) in area.institutions.iter().enumerate() {
+                    let (px, py) = ((n as f32 % plots), (n as f32 / plots).floor());
+                    let position = Point {
+                        x: i as f32 * CELL + CELL * 0.2 + (px + 0.5) * plot_size,
+                        y: j as f32 * CELL + CELL * 0.2 + (py + 0.5) * plot_size,
+                    };
+                    buildings.push(MapBuilding {
+                        institution: institution_id.clone(),
+                        name: self.institution_name(institution_id),
+                        position: rotate(position, turns),
+                        size: plot_size * 0.6,
+                    });
+                }
+
+                districts.push(MapDistrict {
+                    area: area_id.clone(),
+                    name: area.name.clone(),
+                    cell: (i, j),
+                    outline,
+                    centre,
+                    coastal: coastal && j == 0,
+                });
+            }
+
+            let by_cell: BTreeMap<(i32, i32), Point> =
+                districts.iter().map(|d| (d.cell, d.centre)).collect();
+            let mut roads: Vec<(Point, Point)> = Vec::new();
+            for ((i, j), centre) in &by_cell {
+                for neighbour in [(i + 1, *j), (*i, j + 1)] {
+                    if by_cell.contains_key(&neighbour) {
+                        roads.push((*centre, *by_cell.get(&neighbour).unwrap()));
+                    }
+                }
+            }
+
+            let min_i = cells.iter().map(|c| c.0).min().unwrap_or(0) - 1;
+            let max_i = cells.iter().map(|c| c.0).max().unwrap_or(0) + 2;
+            let min_j = cells.iter().map(|c| c.1).min().unwrap_or(0) - 1;
+            let coast = if coastal {
+                let mut sea: Vec<Point> = Vec::new();
+                let steps = ((max_i - min_i) * 4) as usize;
+                for step in 0..=steps {
+                    sea.push(Point {
+                        x: min_i as f32 * CELL + step as f32 * CELL / 4.0,
+                        y: CELL * (1.08 + rng.gen::<f32>() * 0.1),
+                    });
+                }
+                sea.push(Point {
+                    x: max_i as f32 * CELL,
+                    y: CELL * 2.5,
+                });
+                sea.push(Point {
+                    x: min_i as f32 * CELL,
+                    y: CELL * 2.5,
+                });
+                // a pier for each of the city's docks on the shore
+                let mut docks: Vec<Vec<Point>> = Vec::new();
+                for district in districts.iter().filter(|d| d.coastal) {
+                    let piers = self.docks_in(&district.area);
+                    for pier in 0..piers {
+                        let x = district.cell.0 as f32 * CELL
+                            + CELL * (pier as f32 + 1.0) / (piers as f32 + 1.0);
+                        docks.push(vec![
+                            Point {
+                                x: x - 6.0,
+                                y: CELL,
+                            },
+                            Point {
+                                x: x + 6.0,
+                                y: CELL,
+                            },
+                            Point {
+                                x: x + 6.0,
+                                y: CELL * 1.35,
+                            },
+                            Point {
+                                x: x - 6.0,
+                                y: CELL * 1.35,
+                            },
+                        ]);
+                    }
+                }
+                Some(Coast {
+                    name: sea_name(dict, &mut rng),
+                    label: rotate(
+                        Point {
+                            x: (min_i + max_i) as f32 * CELL / 2.0,
+                            y: CELL * 1.9,
+                        },
+                        turns,
+                    ),
+                    sea: sea.into_iter().map(|p| rotate(p, turns)).collect(),
+                    docks: docks
+                        .into_iter()
+                        .map(|d| d.into_iter().map(|p| rotate(p, turns)).collect())
+                        .collect(),
+                })
+            } else {
+                None
+            };
+
+            let corners: Vec<Point> = [
+                (min_i, min_j),
+                (max_i, min_j),
+                (min_i, if coastal { 3 } else { min_j + 2 }),
+                (max_i, if coastal { 3 } else { min_j + 2 }),
+            ]
+            .iter()
+            .map(|(x, y)| {
+                rotate(
+                    Point {
+                        x: *x as f32 * CELL,
+                        y: *y as f32 * CELL,
+                    },
+                    turns,
+                )
+            })
+            .chain(districts.iter().flat_map(|d| d.outline.iter().cloned()))
+            .collect();
+            return CityMap {
+                min: Point {
+                    x: corners.iter().map(|p| p.x).fold(f32::MAX, f32::min) - MARGIN,
+                    y: corners.iter().map(|p| p.y).fold(f32::MAX, f32::min) - MARGIN,
+                },
+                max: Point {
+                    x: corners.iter().map(|p| p.x).fold(f32::MIN, f32::max) + MARGIN,
+                    y: corners.iter().map(|p| p.y).fold(f32::MIN, f32::max) + MARGIN,
+                },
+                districts,
+                roads,
+                buildings,
+                coast,
+            };
+        }
+
+        pub fn export_map(self: &Self, dict: &Dictionary) -> String {
+            let map = self.generate_map(self.dictionary_for(dict, &self.culture.id));
+            let (width, height) = (map.max.x - map.min.x, map.max.y - map.min.y);
+            let mut output = String::new();
+            output += &format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{:.1} {:.1} {:.1} {:.1}\" width=\"{:.0}\" height=\"{:.0}\" font-family=\"serif\">\n",
+                map.min.x, map.min.y, width, height, width, height
+            );
+            output += &format!("  <title>{}</title>\n", escape(&self.name));
+            output += &format!(
+                "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#e8dfc4\"/>\n",
+                map.min.x, map.min.y, width, height
+            );
+            if map.coast.is_some() {
+                let coast = map.coast.as_ref().unwrap();
+                output += &format!(
+                    "  <polygon points=\"{}\" fill=\"#8fb8d6\" stroke=\"#5a7f9c\" stroke-width=\"3\"/>\n",
+                    path(&coast.sea)
+                );
+                for dock in &coast.docks {
+                    output += &format!(
+                        "  <polygon points=\"{}\" fill=\"#8a6a45\" stroke=\"#4d3a25\"/>\n",
+                        path(dock)
+                    );
+                }
+                output += &format!(
+                    "  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"36\" font-style=\"italic\" fill=\"#2f5470\" text-anchor=\"middle\">{}</text>\n",
+                    coast.label.x,
+                    coast.label.y,
+                    escape(&coast.name)
+                );
+            }
+            for (n, district) in map.districts.iter().enumerate() {
+                let hue = (n * 47) % 360;
+                output += &format!(
+                    "  <polygon points=\"{}\" fill=\"hsl({}, 35%, 75%)\" stroke=\"#6b5b45\" stroke-width=\"2\"/>\n",
+                    path(&district.outline),
+                    hue
+                );
+            }
+            for (from, to) in &map.roads {
+                output += &format!(
+                    "  <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#a08a66\" stroke-width=\"6\" stroke-linecap=\"round\"/>\n",
+                    from.x, from.y, to.x, to.y
+                );
+            }
+            for building in &map.buildings {
+                output += &format!(
+                    "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#7a4b3a\" stroke=\"#3d251d\"><title>{}</title></rect>\n",
+                    building.position.x - building.size / 2.0,
+                    building.position.y - building.size / 2.0,
+                    building.size,
+                    building.size,
+                    escape(&building.name)
+                );
+                output += &format!(
+                    "  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"7\" text-anchor=\"middle\">{}</text>\n",
+                    building.position.x,
+                    building.position.y + building.size / 2.0 + 8.0,
+                    escape(&building.name)
+                );
+            }
+            for district in &map.districts {
+                output += &format!(
+                    "  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"20\" font-weight=\"bold\" fill=\"#3b2f20\" text-anchor=\"middle\" stroke=\"#f4efe1\" stroke-width=\"4\" paint-order=\"stroke\">{}</text>\n",
+                    district.centre.x,
+                    district.centre.y,
+                    escape(&district.name)
+                );
+            }
+            output += &format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"48\" font-variant=\"small-caps\" fill=\"#3b2f20\" text-anchor=\"middle\">{}</text>\n",
+                map.min.x + width / 2.0,
+                map.min.y + MARGIN * 0.6,
+                escape(&self.name)
+            );
+            output += "</svg>\n";
+            return output;
+        }
+    }
+
+    #[test]
+    fn test_map_places_every_district() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+            institutions::institutions::generate_institution,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            150,
+            21,
+        );
+        for _i in 0..20 {
+            city.simulate_year(&dict);
+        }
+        let map = city.generate_map(&dict);
+        assert_eq!(map.districts.len(), city.areas.len());
+        assert_eq!(
+            map.buildings.len(),
+            city.areas
+                .values()
+                .map(|a| a.institutions.len())
+                .sum::<usize>()
+        );
+        let cells: Vec<(i32, i32)> = map.districts.iter().map(|d| d.cell).collect();
+        assert!(cells
+            .iter()
+            .enumerate()
+            .all(|(n, c)| !cells[n + 1..].contains(c)));
+        assert_eq!(map.coast.is_some(), !city.culture.landlocked);
+        let svg = city.export_map(&dict);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(&escape(&city.name)));
+        for area in city.areas.values() {
+            assert!(svg.contains(&escape(&area.name)));
+        }
+
+        // laying out a new district leaves the old ones where they were
+        city.culture.landlocked = false;
+        let before = city.generate_map(&dict);
+        let mut rng = seeded_rng(0, "test", 0);
+        city.year += 1;
+        let space = city.config.areas.max_size * 10;
+        city.find_area_with_room(&dict, space, &mut rng);
+        let after = city.generate_map(&dict);
+        assert_eq!(after.districts.len(), before.districts.len() + 1);
+        assert_eq!(
+            after.districts[..before.districts.len()],
+            before.districts[..]
+        );
+
+        // piers are only drawn for the docks the city has on its shore
+        assert_eq!(before.coast.as_ref().unwrap().docks.len(), 0);
+        let shore = after.districts.iter().find(|d| d.coastal).unwrap().area;
+        let dock = generate_institution(&dict, &city, InsitutionType::Dock, &mut rng);
+        city.areas
+            .get_mut(&shore)
+            .unwrap()
+            .institutions
+            .insert(dock.id.clone());
+        city.institutions.insert(dock.id.clone(), dock);
+        assert_eq!(city.generate_map(&dict).coast.unwrap().docks.len(), 1);
+    }
+}
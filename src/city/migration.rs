@@ -0,0 +1,312 @@
+pub mod migration {
+    use std::collections::BTreeSet;
+
+    use procgen_templater::dictionary::dictionary::Dictionary;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+
+    use crate::city::{
+        chronicle::chronicle::Event,
+        city::City,
+        culture::culture::{random_culture, CultureId},
+        population::mind::{
+            mind::{random_mind, Mind, MindId},
+            relations::{partners::partners::is_sexuality_compatible, relations::RelationVerb},
+        },
+        site::site::random_site,
+    };
+
+    // draws for a partner whose sexuality suits an arriving mind before they come alone
+    const PARTNER_ATTEMPTS: usize = 5;
+
+    fn relate(minds: &mut Vec<Mind>, from: usize, verb: RelationVerb, to: usize) {
+        let to_id = minds[to].id.clone();
+        let relations = &mut minds[from].relations;
+        if !relations.contains_key(&verb) {
+            relations.insert(verb.clone(), BTreeSet::new());
+        }
+        relations.get_mut(&verb).unwrap().insert(to_id);
+    }
+
+    impl City {
+        // the share of adults able to work who have a job
+        pub fn prospects(self: &Self) -> f32 {
+            let workers: Vec<&Mind> = self
+                .population
+                .values()
+                .filter(|m| {
+                    m.alive
+                        && m.age > self.culture_by_id(&m.culture).adult_age
+                        && m.health.can_work(&self.config.health)
+                })
+                .collect();
+            if workers.len() == 0 {
+                return 1.0;
+            }
+            let employed = workers.iter().filter(|m| m.employer.is_some()).count();
+            return employed as f32 / workers.len() as f32;
+        }
+
+        // how many minds arrived and left in the year
+        pub fn migration_in_year(self: &Self, year: usize) -> (usize, usize) {
+            let mut arrived = 0;
+            let mut left = 0;
+            for event in self.chronicle.in_year(year) {
+                match event {
+                    Event::Immigrated { minds, .. } => arrived += minds.len(),
+                    Event::Emigrated { minds, .. } => left += minds.len(),
+                    _ => {}
+                }
+            }
+            return (arrived, left);
+        }
+
+        // any town will do, the features of a place beyond the city aren't known
+        fn random_origin(self: &Self, dict: &Dictionary, rng: &mut StdRng) -> String {
+            return self
+                .templates
+                .get_random_template(vec![vec!["AreaName".to_string()]], rng)
+                .unwrap()
+                .render_as_title(dict, rng)
+                .unwrap();
+        }
+
+        // the culture arrivals belong to, a new minority comes from a land of its own
+        fn arriving_culture(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) -> CultureId {
+            let config = &self.config.migration;
+            if rng.gen::<f32>() >= config.minority_chance {
+                return self.culture.id.clone();
+            }
+            let mut options: Vec<Option<CultureId>> =
+                self.minorities.keys().cloned().map(Some).collect();
+            if self.minorities.len() < config.max_minorities {
+                options.push(None);
+            }
+            if options.len() == 0 {
+                return self.culture.id.clone();
+            }
+            let choice = options.choose(rng).unwrap().clone();
+            if choice.is_some() {
+                return choice.unwrap();
+            }
+            let raw = self.dictionaries.raw();
+            let site = random_site(&raw, rng);
+            let mut culture =
+                random_culture(&raw, &self.templates, &site, self.culture.era.clone(), rng);
+            culture.homeland = self.random_origin(dict, rng);
+            let id = culture.id.clone();
+            self.add_minority(culture);
+            return id;
+        }
+
+        // a single adult or a couple with their children, from a town beyond the city
+        fn arrive(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            let config = self.config.migration.clone();
+            let year = self.year as i32;
+            let culture_id = self.arriving_culture(dict, rng);
+            let culture = self.culture_by_id(&culture_id);
+            let names = self.dictionary_for(dict, &culture_id);
+            let demographics = &self.config.demographics;
+            let mut minds = vec![random_mind(names, culture, demographics, year, rng)];
+            if rng.gen::<f32>() < config.family_chance {
+                for _attempt in 0..PARTNER_ATTEMPTS {
+                    let partner = random_mind(names, culture, demographics, year, rng);
+                    if is_sexuality_compatible(&minds[0], &partner) {
+                        minds.push(partner);
+                        relate(&mut minds, 0, RelationVerb::Spouse, 1);
+                        relate(&mut minds, 1, RelationVerb::Spouse, 0);
+                        break;
+                    }
+                }
+            }
+            if minds.len() == 2 {
+                for _i in 0..rng.gen_range(0..=config.max_children) {
+                    let mut child = random_mind(names, culture, demographics, year, rng);
+                    child.age = rng.gen_range(0..culture.adult_age);
+                    child.year_of_birth = year - child.age as i32;
+                    child.last_name = minds[0].last_name.clone();
+                    child.origional_last_name = child.last_name.clone();
+                    minds.push(child);
+                    let c = minds.len() - 1;
+                    for parent in [0, 1] {
+                        relate(&mut minds, c, RelationVerb::Parent, parent);
+                        relate(&mut minds, parent, RelationVerb::Child, c);
+                    }
+                }
+            }
+
+            let origin = if self.minorities.contains_key(&culture_id) {
+                self.culture_by_id(&culture_id).homeland.clone()
+            } else {
+                self.random_origin(dict, rng)
+            };
+            let ids: Vec<MindId> = minds.iter().map(|m| m.id.clone()).collect();
+            for mut mind in minds {
+                mind.origin = Some(origin.clone());
+                self.population.insert(mind.id.clone(), mind);
+            }
+            for id in ids.iter().skip(2) {
+                self.generate_family_relations(id);
+            }
+            self.record_event(Event::Immigrated {
+                year: self.year,
+                minds: ids.clone(),
+                origin,
+            });
+            self.found_household(dict, ids, rng);
+        }
+
+        fn emigrate(self: &mut Self, ids: Vec<MindId>) {
+            for id in &ids {
+                let mind = self.population.get_mut(id).unwrap();
+                mind.emigrate(self.year);
+                let employer = mind.employer.take();
+                if employer.is_some() && self.institutions.contains_key(&employer.unwrap()) {
+                    self.institutions
+                        .get_mut(&employer.unwrap())
+                        .unwrap()
+                        .staff
+                        .remove(id);
+                }
+                self.end_role(id);
+                self.vacate_mind(id);
+                self.remove_from_household(id);
+            }
+            self.record_event(Event::Emigrated {
+                year: self.year,
+                minds: ids,
+            });
+        }
+
+        // Newcomers arrive while there is work to be had. A head of household who is out of
+        // work, or who has no partner, may leave with everyone under their roof, so an employed
+        // head with a partner stays. Other single adults may leave alone.
+        pub fn migrate(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            let config = self.config.migration.clone();
+            let prospects = self.prospects();
+            let adult = |m: &Mind| m.age > self.culture_by_id(&m.culture).adult_age;
+
+            let chance = |m: &Mind| {
+                let unemployed = if m.employer.is_none() { 2.0 } else { 1.0 };
+                return config.emigration_rate * (2.0 - prospects) * unemployed;
+            };
+            let mut leaving: Vec<Vec<MindId>> = Vec::new();
+            for household in self.households.values() {
+                let head = self.population.get(&household.head).unwrap();
+                if !head.alive {
+                    continue;
+                }
+                if (head.employer.is_none() || head.is_single())
+                    && adult(head)
+                    && rng.gen::<f32>() < chance(head)
+                {
+                    leaving.push(
+                        household
+                            .members
+                            .iter()
+                            .filter(|id| self.population.get(id).unwrap().alive)
+                            .cloned()
+                            .collect(),
+                    );
+                    continue;
+                }
+                for m_id in &household.members {
+                    let mind = self.population.get(m_id).unwrap();
+                    if !m_id.eq(&household.head)
+                        && mind.alive
+                        && mind.is_single()
+                        && adult(mind)
+                        && rng.gen::<f32>() < chance(mind)
+                    {
+                        leaving.push(vec![m_id.clone()]);
+                    }
+                }
+            }
+            for ids in leaving {
+                self.emigrate(ids);
+            }
+
+            let expected = config.arrivals_per_year * prospects;
+            let mut arrivals = expected as usize;
+            if rng.gen::<f32>() < expected.fract() {
+                arrivals += 1;
+            }
+            for _i in 0..arrivals {
+                self.arrive(dict, rng);
+            }
+            self.add_timestamp("migrate");
+        }
+    }
+
+    #[test]
+    fn test_migration() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut config = SimulationConfig::default();
+        config.migration.arrivals_per_year = 3.0;
+        config.migration.emigration_rate = 0.1;
+        let mut city = random_city(&dict, &templates, &config, Era::Modern, 80, 5);
+        for _i in 0..15 {
+            city.simulate_year(&dict);
+        }
+        let (arrived, left) = (1..=city.year)
+            .map(|year| city.migration_in_year(year))
+            .fold((0, 0), |acc, (a, l)| (acc.0 + a, acc.1 + l));
+        assert!(arrived > 0 && left > 0);
+
+        let emigrants: Vec<&Mind> = city
+            .population
+            .values()
+            .filter(|m| m.emigrated.is_some())
+            .collect();
+        assert_eq!(emigrants.len(), left);
+        for emigrant in emigrants {
+            assert!(!emigrant.alive && emigrant.cause_of_death.is_none());
+            assert!(city.household_of(&emigrant.id).is_none());
+            assert!(city
+                .institutions
+                .values()
+                .all(|i| !i.staff.contains_key(&emigrant.id)));
+        }
+        let immigrant = city
+            .population
+            .values()
+            .find(|m| m.alive && m.origin.is_some())
+            .unwrap();
+        assert!(city.household_of(&immigrant.id).is_some());
+        assert!(immigrant
+            .biography(&city)
+            .contains(immigrant.origin.as_ref().unwrap()));
+
+        // with everyone free to go, employed heads with a partner still stay, and only single
+        // adults leave on their own
+        city.config.migration.emigration_rate = 10.0;
+        city.config.migration.arrivals_per_year = 0.0;
+        city.year += 1;
+        let staying: Vec<MindId> = city
+            .households
+            .values()
+            .map(|h| city.population.get(&h.head).unwrap())
+            .filter(|head| head.alive && head.employer.is_some() && !head.is_single())
+            .map(|head| head.id.clone())
+            .collect();
+        assert!(staying.len() > 0);
+        let mut rng = city.rng("migration");
+        city.migrate(&dict, &mut rng);
+        for id in &staying {
+            assert!(city.population.get(id).unwrap().alive);
+        }
+        for event in city.chronicle.in_year(city.year) {
+            if let Event::Emigrated { minds, .. } = event {
+                if minds.len() == 1 {
+                    assert!(city.population.get(&minds[0]).unwrap().is_single());
+                }
+            }
+        }
+    }
+}
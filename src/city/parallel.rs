@@ -0,0 +1,98 @@
+pub mod parallel {
+    // Per-mind phases of a year are split in two: every mind works out what it wants to do
+    // from the city as it stood at the start of the phase, then the proposals are applied
+    // one at a time in mind order. Only the first half runs in parallel, and each mind draws
+    // from its own rng, so a seed gives the same city with or without the parallel feature.
+    use rand::{rngs::StdRng, Rng};
+    use uuid::Uuid;
+
+    use crate::seeded::seeded::seeded_mind_rng;
+
+    #[cfg(feature = "parallel")]
+    use rayon::prelude::*;
+
+    // the seed every mind's rng in a phase is derived from, drawn once from the system rng
+    pub fn phase_seed(rng: &mut StdRng) -> u64 {
+        return rng.gen();
+    }
+
+    // proposals come back in the same order as the ids, however many threads worked on them
+    #[cfg(feature = "parallel")]
+    pub fn propose_for_minds<T, F>(ids: &Vec<Uuid>, phase_seed: u64, propose: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&Uuid, &mut StdRng) -> T + Sync,
+    {
+        return ids
+            .par_iter()
+            .map(|id| propose(id, &mut seeded_mind_rng(phase_seed, id)))
+            .collect();
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    pub fn propose_for_minds<T, F>(ids: &Vec<Uuid>, phase_seed: u64, propose: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&Uuid, &mut StdRng) -> T + Sync,
+    {
+        return ids
+            .iter()
+            .map(|id| propose(id, &mut seeded_mind_rng(phase_seed, id)))
+            .collect();
+    }
+
+    #[test]
+    fn test_proposals_keep_mind_order() {
+        use rand::SeedableRng;
+        let mut rng = StdRng::seed_from_u64(3);
+        let ids: Vec<Uuid> = (0..500)
+            .map(|_i| crate::seeded::seeded::random_id(&mut rng))
+            .collect();
+        let seed = phase_seed(&mut rng);
+        let draw = |id: &Uuid, rng: &mut StdRng| (id.clone(), rng.gen::<u32>());
+        let first = propose_for_minds(&ids, seed, draw);
+        assert_eq!(
+            first
+                .iter()
+                .map(|(id, _)| id.clone())
+                .collect::<Vec<Uuid>>(),
+            ids
+        );
+        assert_eq!(first, propose_for_minds(&ids, seed, draw));
+    }
+
+    // The same seed has to give the same city with or without the parallel feature, so both
+    // builds check a city against one hash. Run with and without --features parallel, and
+    // when the simulation changes on purpose take the new hash from the failed assert.
+    #[test]
+    fn test_parallel_matches_serial() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::{
+            seeded::stream_hash, templates::templates::build_templates_from_folder,
+        };
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            80,
+            17,
+        );
+        for _i in 0..15 {
+            city.simulate_year(&dict);
+        }
+        let path = std::env::temp_dir().join(format!(
+            "city_generator_parallel_test_{}.json",
+            cfg!(feature = "parallel")
+        ));
+        city.save(path.to_str().unwrap()).unwrap();
+        let snapshot = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(stream_hash(&snapshot), 6478023601269067553);
+    }
+}
@@ -1,10 +1,10 @@
 pub mod mind;
 pub mod population {
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
 
     use uuid::Uuid;
 
     use super::mind::mind::Mind;
 
-    pub type Population = HashMap<Uuid, Mind>;
+    pub type Population = BTreeMap<Uuid, Mind>;
 }
@@ -1,26 +1,40 @@
+pub mod biography;
+pub mod health;
 pub mod personality;
 pub mod physical_description;
 pub mod relations;
 pub mod mind {
     use crate::{
-        city::{city::City, culture::culture::Culture, dieties::dieties::Diety},
+        city::{
+            area::area::AreaId,
+            careers::careers::Role,
+            city::City,
+            config::config::{DemographicConfig, HealthConfig, MortalityConfig},
+            culture::culture::{Culture, CultureId},
+            dieties::dieties::Diety,
+            house::house::HouseId,
+            household::household::HouseholdId,
+        },
         grammar::grammar::{a_or_an, render_list},
+        seeded::seeded::{random_id, random_word},
     };
 
     use super::{
+        health::health::Health,
         personality::personality::{random_personality, Personality},
         physical_description::physical_description::{random_description, PhysicalDescription},
         relations::relations::RelationVerb,
     };
     use procgen_templater::dictionary::{dictionary::Dictionary, word::word::WordType};
-    use rand::Rng;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+    use serde::{Deserialize, Serialize};
     use std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeMap, BTreeSet},
         fmt,
     };
     use uuid::Uuid;
 
-    #[derive(PartialEq, Debug, Clone, Hash, Eq)]
+    #[derive(PartialEq, Debug, Clone, Hash, Eq, Serialize, Deserialize)]
     pub enum Gender {
         Male,
         Female,
@@ -54,9 +68,7 @@ pub mod mind {
         RelationVerb::Acquaintance,
     ];
 
-    const AMBIGUOUS_GENDER_CHANCE: f32 = 0.1;
-
-    #[derive(PartialEq, Debug, Clone, Hash, Eq)]
+    #[derive(PartialEq, Debug, Clone, Hash, Eq, Serialize, Deserialize)]
     pub enum Sexuality {
         Hetrosexual,
         Homosexual,
@@ -75,13 +87,46 @@ pub mod mind {
         }
     }
 
-    const HOMOSEXUALITY_CHANCE: f32 = 0.075;
-    const ASEXUALITY_CHANCE: f32 = 0.05;
-    const BISEXUALITY_CHANCE: f32 = 0.075;
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub enum CauseOfDeath {
+        OldAge,
+        Illness,
+        Accident,
+        Childbirth,
+        Violence,
+        // the name of the disease
+        Epidemic(String),
+    }
+
+    impl fmt::Display for CauseOfDeath {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                CauseOfDeath::OldAge => write!(f, "old age"),
+                CauseOfDeath::Illness => write!(f, "illness"),
+                CauseOfDeath::Accident => write!(f, "an accident"),
+                CauseOfDeath::Childbirth => write!(f, "childbirth"),
+                CauseOfDeath::Violence => write!(f, "violence"),
+                CauseOfDeath::Epidemic(disease) => write!(f, "{}", disease),
+            }
+        }
+    }
+
+    impl CauseOfDeath {
+        // how a death reads in the chronicle, "{name} {phrase}"
+        pub fn describe(self: &Self) -> String {
+            return match self {
+                CauseOfDeath::OldAge | CauseOfDeath::Illness | CauseOfDeath::Epidemic(_) => {
+                    format!("died of {}", self)
+                }
+                CauseOfDeath::Accident | CauseOfDeath::Childbirth => format!("died in {}", self),
+                CauseOfDeath::Violence => String::from("was killed"),
+            };
+        }
+    }
 
     pub type MindId = Uuid;
 
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct Mind {
         pub id: MindId,
         pub alive: bool,
@@ -91,34 +136,160 @@ pub mod mind {
         pub age: u32,
         pub gender: Gender,
         pub sexuality: Sexuality,
-        pub relations: HashMap<RelationVerb, HashSet<Uuid>>,
+        pub relations: BTreeMap<RelationVerb, BTreeSet<Uuid>>,
         pub description: PhysicalDescription,
         pub personality: Personality,
-        pub dieties: HashSet<Uuid>,
+        pub dieties: BTreeSet<Uuid>,
         pub employer: Option<Uuid>,
         pub year_of_birth: i32,
+        pub area: Option<AreaId>,
+        pub household: Option<HouseholdId>,
+        pub cause_of_death: Option<CauseOfDeath>,
+        pub health: Health,
+        // where an immigrant came from
+        pub origin: Option<String>,
+        // the year the mind left the city, emigrants are no longer alive to the city
+        pub emigrated: Option<usize>,
+        pub culture: CultureId,
+        pub house: Option<HouseId>,
+        // every post the mind has held, the current one last
+        pub career: Vec<Role>,
     }
 
     impl Mind {
+        // what the mind dies of in the year it turns a year older, if it dies, decided without
+        // changing it so every mind can be rolled for at once
+        pub fn dies_this_year(
+            self: &Self,
+            mortality: &MortalityConfig,
+            health: &HealthConfig,
+            culture: &Culture,
+            rng: &mut StdRng,
+        ) -> Option<CauseOfDeath> {
+            let age = self.age + 1;
+            let adult = age >= culture.adult_age;
+            let medicine = mortality.medicine(&culture.era);
+            let illness = if age <= 1 {
+                mortality.infant_mortality
+            } else if !adult {
+                mortality.child_mortality
+            } else {
+                mortality.illness_rate
+            };
+            let chances = [
+                (
+                    CauseOfDeath::OldAge,
+                    mortality.old_age_chance(
+                        age,
+                        culture.avg_lifespan,
+                        culture.avg_lifespan_variance,
+                    ),
+                ),
+                (
+                    CauseOfDeath::Illness,
+                    (illness + self.health.illness_chance(health)) * medicine,
+                ),
+                (
+                    CauseOfDeath::Accident,
+                    mortality.accident_rate + self.health.injury_chance(health),
+                ),
+                (
+                    CauseOfDeath::Violence,
+                    if adult { mortality.violence_rate } else { 0.0 },
+                ),
+            ];
+            for (cause, chance) in chances {
+                if rng.gen::<f32>() < chance {
+                    return Some(cause);
+                }
+            }
+            return None;
+        }
         pub fn age(self: &mut Self) {
             if self.alive {
                 self.age += 1;
-                let death_threashhold =
-                    ((self.age as f32 - 30.0) / 30.0).max(0.01).powf(2.25) * 0.12;
-                // println!("Death Threshhold {}: {:.2}", self.age, death_threashhold);
-                let mut rng = rand::thread_rng();
-                if rng.gen::<f32>() < death_threashhold {
-                    self.alive = false;
-                }
             }
         }
+        pub fn die(self: &mut Self, cause: CauseOfDeath) {
+            self.alive = false;
+            self.cause_of_death = Some(cause);
+        }
+        pub fn emigrate(self: &mut Self, year: usize) {
+            self.alive = false;
+            self.emigrated = Some(year);
+        }
+        pub fn status(self: &Self) -> String {
+            return if self.alive {
+                String::from("Alive")
+            } else if self.emigrated.is_some() {
+                format!("Emigrated in year {}", self.emigrated.unwrap())
+            } else if self.cause_of_death.is_some() {
+                format!("Dead of {}", self.cause_of_death.as_ref().unwrap())
+            } else {
+                String::from("Dead")
+            };
+        }
         pub fn print(self: &Self, city: &City) -> String {
             let mut output = String::new();
 
-            output += &format!("### {} {}  \n", self.first_name, self.last_name);
+            output += &format!("### {}  \n", city.titled_name(&self.id));
             output += &format!("Age: {}  \n", self.age);
             output += &format!("Born: {}  \n", self.year_of_birth);
-            output += &format!("Status: {}  \n", if self.alive { "Alive" } else { "Dead" });
+            output += &format!(
+                "Status: {}  \n",
+                if self.alive {
+                    "Alive"
+                } else if self.emigrated.is_some() {
+                    "Emigrated"
+                } else {
+                    "Dead"
+                }
+            );
+            if self.emigrated.is_some() {
+                output += &format!("Left in: {}  \n", self.emigrated.unwrap());
+            }
+            if self.cause_of_death.is_some() {
+                output += &format!(
+                    "Cause of death: {}  \n",
+                    self.cause_of_death.as_ref().unwrap()
+                );
+            }
+            if self.origin.is_some() {
+                output += &format!("Came from: {}  \n", self.origin.as_ref().unwrap());
+            }
+            if city.minorities.contains_key(&self.culture) {
+                output += &format!(
+                    "Culture: {}  \n",
+                    city.culture_by_id(&self.culture).homeland
+                );
+            }
+            if self.area.is_some() {
+                output += &format!("District: {}  \n", city.area_name(&self.area.unwrap()));
+            }
+            if self.house.is_some() {
+                output += &format!("House: {}  \n", city.house_of(&self.id).unwrap().name);
+            }
+            for reign in city.reigns_of(&self.id) {
+                output += &format!(
+                    "Ruled as {}: {} to {}  \n",
+                    reign.title,
+                    reign.began,
+                    if reign.ended.is_some() {
+                        reign.ended.unwrap().to_string()
+                    } else {
+                        String::from("now")
+                    }
+                );
+            }
+            if self.alive && self.health.describe().is_some() {
+                output += &format!("Health: {}  \n", self.health.describe().unwrap());
+                if self.health.cared_for_by.is_some() {
+                    output += &format!(
+                        "Cared for by: {}  \n",
+                        city.institution_name(&self.health.cared_for_by.unwrap())
+                    );
+                }
+            }
             output += &format!("Sexuality: {}  \n", self.sexuality);
             output += &format!("Appearance: {}  \n", self.description.render(None));
 
@@ -136,11 +307,23 @@ pub mod mind {
                 output += "Dieties:  \n";
 
                 for d_id in &self.dieties {
-                    let diety = city.culture.dieties.get(&d_id).unwrap();
+                    let diety = city
+                        .culture_by_id(&self.culture)
+                        .dieties
+                        .get(&d_id)
+                        .unwrap();
                     output += &format!(" - {}.  \n", diety.render_summary());
                 }
             }
 
+            let career = city.describe_career(&self.id);
+            if career.len() > 0 {
+                output += "Career:  \n";
+                for role in career {
+                    output += &format!(" - {}.  \n", role);
+                }
+            }
+
             if self.relations.len() > 0 {
                 output += "\nRelations:  \n";
                 // TODO - order this
@@ -165,7 +348,7 @@ pub mod mind {
                 self.gender,
                 self.age,
                 self.year_of_birth,
-                if self.alive { "Alive" } else { "Dead" }
+                self.status()
             );
             println!(
                 "  They are {} and {}",
@@ -173,6 +356,9 @@ pub mod mind {
                 if self.is_single() { "Single" } else { "Taken" }
             );
             println!("  {}", self.description.render(None));
+            if self.alive && self.health.describe().is_some() {
+                println!("  Health: {}", self.health.describe().unwrap());
+            }
             let traits: Vec<String> = self
                 .personality
                 .traits
@@ -184,9 +370,19 @@ pub mod mind {
                 render_list(traits.iter().map(|t| t.as_str()).collect())
             );
             for d_id in &self.dieties {
-                let diety = city.culture.dieties.get(&d_id).unwrap();
+                let diety = city
+                    .culture_by_id(&self.culture)
+                    .dieties
+                    .get(&d_id)
+                    .unwrap();
                 println!("  They worship {}. {}.", diety.name, diety.render_summary());
             }
+            let home = city.describe_home(&self.id);
+            if home.is_some() {
+                println!("  {}", home.unwrap());
+            } else if self.area.is_some() {
+                println!("  They live in {}", city.area_name(&self.area.unwrap()));
+            }
             if self.employer.is_some() {
                 let employer = city.institutions.get(&self.employer.unwrap()).unwrap();
                 let position = employer.staff.get(&self.id).unwrap();
@@ -271,10 +467,10 @@ pub mod mind {
             }
             return false;
         }
-        pub fn get_relations(self: &Self, verb: RelationVerb) -> HashSet<Uuid> {
+        pub fn get_relations(self: &Self, verb: RelationVerb) -> BTreeSet<Uuid> {
             let rel = self.relations.get(&verb);
             if rel.is_none() {
-                return HashSet::new();
+                return BTreeSet::new();
             } else {
                 return rel.unwrap().clone();
             }
@@ -289,73 +485,102 @@ pub mod mind {
         }
     }
 
-    pub fn random_mind(dict: &Dictionary, culture: &Culture, year: i32) -> Mind {
-        let gender = random_gender();
-        let personality = random_personality(&culture);
-        let mut dieties: HashSet<Uuid> = HashSet::new();
+    pub fn random_mind(
+        dict: &Dictionary,
+        culture: &Culture,
+        demographics: &DemographicConfig,
+        year: i32,
+        rng: &mut StdRng,
+    ) -> Mind {
+        let gender = random_gender(demographics, rng);
+        let personality = random_personality(&culture, rng);
+        let mut dieties: BTreeSet<Uuid> = BTreeSet::new();
         if personality.thiest {
             let diety_list: Vec<&Diety> = culture.dieties.values().collect();
-            let random_diety = diety_list
-                .get((rand::thread_rng().gen::<f32>() * diety_list.len() as f32) as usize)
-                .unwrap();
+            let random_diety = diety_list.choose(rng).unwrap();
             dieties.insert(random_diety.id.clone());
         }
-        let age_offset = rand::thread_rng().gen::<f32>() * 30.0;
-        let last_name = dict
-            .get_random_word((
+        let age_offset = rng.gen::<f32>() * 30.0;
+        let last_name = random_word(
+            dict,
+            (
                 WordType::Noun,
-                vec![vec!["LastName".to_string()], vec![culture.era.to_string()]],
-            ))
-            .unwrap()
-            .base
-            .clone();
+                vec![vec!["LastName".to_string()], culture.era.tags()],
+            ),
+            rng,
+        )
+        .unwrap()
+        .base
+        .clone();
+        // no first names are tagged Ambiguous, both gendered pools share the AmbiguousGender parent
+        let name_gender = if gender.eq(&Gender::Ambiguous) {
+            String::from("AmbiguousGender")
+        } else {
+            gender.to_string()
+        };
         return Mind {
-            id: Uuid::new_v4(),
+            id: random_id(rng),
             alive: true,
             age: culture.adult_age + (age_offset as u32),
-            first_name: dict
-                .get_random_word((
+            first_name: random_word(
+                dict,
+                (
                     WordType::Noun,
                     vec![
                         vec!["FirstName".to_string()],
-                        vec![gender.to_string()],
-                        vec![culture.era.to_string()],
+                        vec![name_gender],
+                        culture.era.tags(),
                     ],
-                ))
-                .unwrap()
-                .base
-                .clone(),
+                ),
+                rng,
+            )
+            .unwrap()
+            .base
+            .clone(),
             last_name: last_name.clone(),
             origional_last_name: last_name,
             gender,
-            sexuality: random_sexuality(),
-            relations: HashMap::new(),
-            description: random_description(&dict),
+            sexuality: random_sexuality(demographics, rng),
+            relations: BTreeMap::new(),
+            description: random_description(&dict, rng),
             personality,
             dieties,
             employer: None,
-            year_of_birth: year - culture.adult_age as i32 + (age_offset as i32),
+            year_of_birth: year - culture.adult_age as i32 - (age_offset as i32),
+            area: None,
+            household: None,
+            cause_of_death: None,
+            health: Health::default(),
+            origin: None,
+            emigrated: None,
+            house: None,
+            career: Vec::new(),
+            culture: culture.id.clone(),
         };
     }
 
-    fn random_gender() -> Gender {
-        let roll = rand::thread_rng().gen::<f32>();
-        if roll < AMBIGUOUS_GENDER_CHANCE {
+    fn random_gender(demographics: &DemographicConfig, rng: &mut StdRng) -> Gender {
+        let roll = rng.gen::<f32>();
+        let ambiguous_chance = demographics.ambiguous_gender_chance;
+        if roll < ambiguous_chance {
             return Gender::Ambiguous;
-        } else if roll < (AMBIGUOUS_GENDER_CHANCE + ((1.0 - AMBIGUOUS_GENDER_CHANCE) / 2.0)) {
+        } else if roll < (ambiguous_chance + ((1.0 - ambiguous_chance) / 2.0)) {
             return Gender::Female;
         } else {
             return Gender::Male;
         }
     }
 
-    fn random_sexuality() -> Sexuality {
-        let roll = rand::thread_rng().gen::<f32>();
-        if roll < HOMOSEXUALITY_CHANCE {
+    fn random_sexuality(demographics: &DemographicConfig, rng: &mut StdRng) -> Sexuality {
+        let roll = rng.gen::<f32>();
+        let homosexuality_chance = demographics.homosexuality_chance;
+        let asexuality_chance = demographics.asexuality_chance;
+        let bisexuality_chance = demographics.bisexuality_chance;
+        if roll < homosexuality_chance {
             return Sexuality::Homosexual;
-        } else if roll < (HOMOSEXUALITY_CHANCE + ASEXUALITY_CHANCE) {
+        } else if roll < (homosexuality_chance + asexuality_chance) {
             return Sexuality::Asexual;
-        } else if roll < (HOMOSEXUALITY_CHANCE + ASEXUALITY_CHANCE + BISEXUALITY_CHANCE) {
+        } else if roll < (homosexuality_chance + asexuality_chance + bisexuality_chance) {
             return Sexuality::Bisexual;
         } else {
             return Sexuality::Hetrosexual;
@@ -366,10 +591,28 @@ pub mod mind {
     fn test_random_mind() {
         use crate::city::city::random_city;
         use crate::city::city::Era;
+        use crate::city::config::config::SimulationConfig;
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
+        };
         use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
         let dict = build_dictionary_from_folder("./data_files");
-        let city = random_city(&dict, Era::Medieval, 1);
-        let m = random_mind(&dict, &city.culture, 0);
+        let templates = build_templates_from_folder("./data_files");
+        let city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            1,
+            0,
+        );
+        let m = random_mind(
+            &dict,
+            &city.culture,
+            &city.config.demographics,
+            0,
+            &mut seeded_rng(0, "test", 0),
+        );
         m.inspect(&city);
     }
 }
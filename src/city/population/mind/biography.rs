@@ -0,0 +1,375 @@
+pub mod biography {
+    use crate::{
+        city::{
+            chronicle::chronicle::Event,
+            city::City,
+            institutions::institutions::InstitutionId,
+            population::mind::{
+                mind::{Mind, MindId},
+                relations::relations::RelationVerb,
+            },
+        },
+        grammar::grammar::{a_or_an, render_list},
+    };
+
+    // relations whose deaths are worth a line in someone's life story, with how to name them
+    const BEREAVEMENT_RELATIONS: [(RelationVerb, &str); 7] = [
+        (RelationVerb::Parent, "parent"),
+        (RelationVerb::Child, "child"),
+        (RelationVerb::Sibling, "sibling"),
+        (RelationVerb::Spouse, "spouse"),
+        (RelationVerb::LateSpouse, "spouse"),
+        (RelationVerb::Partner, "partner"),
+        (RelationVerb::LatePartner, "partner"),
+    ];
+
+    struct Job {
+        institution: InstitutionId,
+        title: String,
+        start: usize,
+        end: Option<usize>,
+    }
+
+    fn years_phrase(years: usize) -> String {
+        return match years {
+            0 => String::from("less than a year"),
+            1 => String::from("a year"),
+            _ => format!("{} years", years),
+        };
+    }
+
+    impl Mind {
+        pub fn biography(self: &Self, city: &City) -> String {
+            let life = city.chronicle.for_mind(&self.id);
+            let paragraphs: Vec<String> = vec![
+                self.birth_paragraph(city, &life),
+                self.career_paragraph(city, &life),
+                self.family_paragraph(city, &life),
+                self.death_paragraph(city, &life),
+            ];
+            return format!(
+                "### {}\n\n{}\n",
+                city.titled_name(&self.id),
+                paragraphs
+                    .iter()
+                    .filter(|p| !p.is_empty())
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join("\n\n")
+            );
+        }
+
+        fn birth_paragraph(self: &Self, city: &City, life: &Vec<&Event>) -> String {
+            let birth_name = format!("{} {}", self.first_name, self.origional_last_name);
+            let parents: Vec<String> = self
+                .get_relations(RelationVerb::Parent)
+                .iter()
+                .map(|id| city.mind_name(id))
+                .collect();
+            let mut output = if self.year_of_birth > 0 {
+                format!("{} was born in year {}", birth_name, self.year_of_birth)
+            } else if self.year_of_birth == 0 {
+                format!("{} was born in the year the city was founded", birth_name)
+            } else {
+                format!(
+                    "{} was born {} before the city was founded",
+                    birth_name,
+                    years_phrase(self.year_of_birth.unsigned_abs() as usize)
+                )
+            };
+            if parents.len() > 0 {
+                output += &format!(
+                    " to {}",
+                    render_list(parents.iter().map(|p| p.as_str()).collect())
+                );
+            }
+            output += ".";
+            let arrival = life.iter().find_map(|e| match e {
+                Event::Immigrated { year, origin, .. } => Some((*year, origin)),
+                _ => None,
+            });
+            if arrival.is_some() {
+                let (year, origin) = arrival.unwrap();
+                output += &format!(" They came to the city from {} in year {}.", origin, year);
+            }
+            if self.last_name.ne(&self.origional_last_name) {
+                output += &format!(
+                    " They took the name {} {} on marrying.",
+                    self.first_name, self.last_name
+                );
+            }
+            return output;
+        }
+
+        fn career_paragraph(self: &Self, city: &City, life: &Vec<&Event>) -> String {
+            let mut jobs: Vec<Job> = Vec::new();
+            for event in life {
+                let open = jobs.last_mut().filter(|j| j.end.is_none());
+                match event {
+                    Event::Hired {
+                        year,
+                        institution,
+                        title,
+                        ..
+                    }
+                    | Event::Promoted {
+                        year,
+                        institution,
+                        title,
+                        ..
+                    }
+                    | Event::ChangedEmployer {
+                        year,
+                        institution,
+                        title,
+                        ..
+                    } => {
+                        if open.is_some() {
+                            open.unwrap().end = Some(*year);
+                        }
+                        jobs.push(Job {
+                            institution: institution.clone(),
+                            title: title.to_ascii_lowercase(),
+                            start: *year,
+                            end: None,
+                        });
+                    }
+                    Event::Fired { year, .. }
+                    | Event::Death { year, .. }
+                    | Event::Emigrated { year, .. } => {
+                        if open.is_some() {
+                            open.unwrap().end = Some(*year);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if jobs.len() == 0 {
+                return if self.age > city.culture_by_id(&self.culture).adult_age {
+                    String::from("They never held a steady job.")
+                } else {
+                    String::new()
+                };
+            }
+
+            let describe = |job: &Job| -> String {
+                return format!(
+                    "{} {} at {}",
+                    a_or_an(&job.title),
+                    job.title,
+                    city.institution_name(&job.institution)
+                );
+            };
+            let past: Vec<String> = jobs
+                .iter()
+                .filter(|j| j.end.is_some())
+                .map(|j| {
+                    format!(
+                        "{} for {}",
+                        describe(j),
+                        years_phrase(j.end.unwrap() - j.start)
+                    )
+                })
+                .collect();
+            let current = jobs.iter().find(|j| j.end.is_none());
+
+            let mut sentences: Vec<String> = Vec::new();
+            if past.len() > 0 {
+                sentences.push(format!(
+                    "They worked as {}.",
+                    render_list(past.iter().map(|p| p.as_str()).collect())
+                ));
+            }
+            if current.is_some() {
+                sentences.push(format!(
+                    "They have worked as {} since year {}.",
+                    describe(current.unwrap()),
+                    current.unwrap().start
+                ));
+            }
+            return sentences.join(" ");
+        }
+
+        // partners and bereavements in the order they happened, then the children
+        fn family_paragraph(self: &Self, city: &City, life: &Vec<&Event>) -> String {
+            let other = |minds: &[MindId; 2]| -> String {
+                let id = if minds[0].eq(&self.id) {
+                    &minds[1]
+                } else {
+                    &minds[0]
+                };
+                return city.mind_name(id);
+            };
+            let mut timeline: Vec<(usize, String)> = Vec::new();
+            let mut children: Vec<String> = Vec::new();
+            for event in life {
+                match event {
+                    Event::Partnered { year, minds } => {
+                        timeline.push((*year, format!("got together with {}", other(minds))))
+                    }
+                    Event::Married { year, minds } => {
+                        timeline.push((*year, format!("married {}", other(minds))))
+                    }
+                    Event::Separated { year, minds } => {
+                        timeline.push((*year, format!("separated from {}", other(minds))))
+                    }
+                    Event::Divorced { year, minds } => {
+                        timeline.push((*year, format!("divorced {}", other(minds))))
+                    }
+                    Event::Birth { year, child, .. } if child.ne(&self.id) => {
+                        let name = city.population.get(child).unwrap().first_name.clone();
+                        children.push(format!("{} in year {}", name, year));
+                    }
+                    Event::Immigrated { minds, .. } => {
+                        let own = self.get_relations(RelationVerb::Child);
+                        for child in minds.iter().filter(|m| own.contains(m)) {
+                            let name = city.population.get(child).unwrap().first_name.clone();
+                            children.push(format!("{}, who came to the city with them", name));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            let died_in = life.iter().find_map(|e| match e {
+                Event::Death { year, .. } => Some(*year),
+                _ => None,
+            });
+            for event in city.chronicle.events() {
+                if let Event::Death { year, mind } = event {
+                    if died_in.is_some() && *year > died_in.unwrap() {
+                        break;
+                    }
+                    let relation = BEREAVEMENT_RELATIONS
+                        .iter()
+                        .find(|(verb, _)| self.get_relations(verb.clone()).contains(mind));
+                    if mind.ne(&self.id) && relation.is_some() {
+                        timeline.push((
+                            *year,
+                            format!(
+                                "lost their {} {}",
+                                relation.unwrap().1,
+                                city.mind_name(mind)
+                            ),
+                        ));
+                    }
+                }
+            }
+            timeline.sort_by_key(|(year, _)| *year);
+
+            let mut sentences: Vec<String> = timeline
+                .iter()
+                .map(|(year, sentence)| format!("In year {} they {}.", year, sentence))
+                .collect();
+            if children.len() == 1 {
+                sentences.push(format!("They had a child, {}.", children[0]));
+            } else if children.len() > 1 {
+                sentences.push(format!(
+                    "They had {} children: {}.",
+                    children.len(),
+                    render_list(children.iter().map(|c| c.as_str()).collect())
+                ));
+            }
+            return sentences.join(" ");
+        }
+
+        fn death_paragraph(self: &Self, city: &City, life: &Vec<&Event>) -> String {
+            if self.alive {
+                return format!("They are alive in year {}, aged {}.", city.year, self.age);
+            }
+            if self.emigrated.is_some() {
+                return format!(
+                    "They left the city in year {} at the age of {}.",
+                    self.emigrated.unwrap(),
+                    self.age
+                );
+            }
+            let died_in = life.iter().find_map(|e| match e {
+                Event::Death { year, .. } => Some(*year),
+                _ => None,
+            });
+            return if died_in.is_some() {
+                format!(
+                    "They died in year {} at the age of {}.",
+                    died_in.unwrap(),
+                    self.age
+                )
+            } else {
+                format!("They died at the age of {}.", self.age)
+            };
+        }
+    }
+
+    #[test]
+    fn test_biography() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            60,
+            7,
+        );
+        for _i in 0..60 {
+            city.simulate_year(&dict);
+        }
+        let parent = city
+            .population
+            .values()
+            .find(|m| {
+                !m.alive && m.emigrated.is_none() && m.get_relations(RelationVerb::Child).len() > 0
+            })
+            .unwrap();
+        let biography = parent.biography(&city);
+        println!("{}", biography);
+        assert!(biography.starts_with(&format!("### {} {}", parent.first_name, parent.last_name)));
+        assert!(biography.contains("They died in year"));
+        assert!(biography.contains("child"));
+
+        let child = city
+            .population
+            .values()
+            .find(|m| m.year_of_birth > 0 && m.origin.is_none())
+            .unwrap();
+        assert!(child
+            .biography(&city)
+            .contains(&format!("was born in year {} to", child.year_of_birth)));
+
+        // the post someone holds now is the one they were last promoted or moved to
+        let promoted = city
+            .population
+            .values()
+            .find(|m| {
+                m.alive
+                    && m.employer.is_some()
+                    && m.career.len() > 1
+                    && m.career.last().unwrap().began > m.career[m.career.len() - 2].began
+            })
+            .unwrap();
+        let role = promoted.career.last().unwrap();
+        assert!(promoted.biography(&city).contains(&format!(
+            "{} at {} since year {}",
+            role.title.to_ascii_lowercase(),
+            city.institution_name(&role.institution),
+            role.began
+        )));
+
+        // emigrants left their work behind with the city
+        let emigrant = city
+            .population
+            .values()
+            .find(|m| m.emigrated.is_some() && m.career.len() > 0)
+            .unwrap();
+        let biography = emigrant.biography(&city);
+        assert!(biography.contains("They left the city in year"));
+        assert!(!biography.contains("They have worked as"));
+    }
+}
@@ -0,0 +1,383 @@
+pub mod health {
+    use std::fmt;
+
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        city::{
+            city::City,
+            config::config::HealthConfig,
+            institutions::institutions::InstitutionId,
+            parallel::parallel::{phase_seed, propose_for_minds},
+            population::mind::mind::MindId,
+        },
+        grammar::grammar::render_list,
+    };
+
+    const CHRONIC_CONDITIONS: [&str; 6] = [
+        "a weak heart",
+        "a wasting cough",
+        "gout",
+        "a palsy",
+        "failing lungs",
+        "a growth",
+    ];
+    const INJURIES: [&str; 6] = [
+        "a broken arm",
+        "a broken leg",
+        "a bad burn",
+        "a deep cut",
+        "cracked ribs",
+        "a head wound",
+    ];
+    const DISABILITIES: [&str; 5] = [
+        "blindness",
+        "deafness",
+        "a lame leg",
+        "a lost hand",
+        "a withered arm",
+    ];
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub enum ConditionKind {
+        Chronic,
+        Injury,
+        Disability,
+        Pregnancy { other_parent: MindId },
+        // caught in an epidemic, named after the disease
+        Infection,
+    }
+
+    // severity runs from 0 to 1, conditions with no end year last for life
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Condition {
+        pub kind: ConditionKind,
+        pub name: String,
+        pub severity: f32,
+        pub since: usize,
+        pub until: Option<usize>,
+    }
+
+    impl fmt::Display for Condition {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.name)
+        }
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize, Default)]
+    pub struct Health {
+        pub conditions: Vec<Condition>,
+        pub cared_for_by: Option<InstitutionId>,
+    }
+
+    impl Health {
+        fn of_kind(self: &Self, kind: ConditionKind) -> impl Iterator<Item = &Condition> + '_ {
+            return self.conditions.iter().filter(move |c| c.kind.eq(&kind));
+        }
+
+        pub fn needs_care(self: &Self) -> bool {
+            return self.conditions.iter().any(|c| {
+                c.kind.eq(&ConditionKind::Chronic)
+                    || c.kind.eq(&ConditionKind::Injury)
+                    || c.kind.eq(&ConditionKind::Infection)
+            });
+        }
+
+        pub fn care_factor(self: &Self, config: &HealthConfig) -> f32 {
+            return if self.cared_for_by.is_some() {
+                config.care_factor
+            } else {
+                1.0
+            };
+        }
+
+        // annual chance of dying of a chronic condition
+        pub fn illness_chance(self: &Self, config: &HealthConfig) -> f32 {
+            let severity: f32 = self
+                .of_kind(ConditionKind::Chronic)
+                .map(|c| c.severity)
+                .sum();
+            return severity * config.chronic_lethality * self.care_factor(config);
+        }
+
+        // annual chance of dying of an injury
+        pub fn injury_chance(self: &Self, config: &HealthConfig) -> f32 {
+            let severity: f32 = self
+                .of_kind(ConditionKind::Injury)
+                .map(|c| c.severity)
+                .sum();
+            return severity * config.injury_lethality * self.care_factor(config);
+        }
+
+        pub fn can_work(self: &Self, config: &HealthConfig) -> bool {
+            return !self.conditions.iter().any(|c| {
+                !matches!(c.kind, ConditionKind::Pregnancy { .. })
+                    && c.severity >= config.work_threshold
+            });
+        }
+
+        // multiplier on the chance of conceiving, nobody conceives while already pregnant
+        pub fn fertility(self: &Self, config: &HealthConfig) -> f32 {
+            if self.pregnancy().is_some() {
+                return 0.0;
+            }
+            return self
+                .of_kind(ConditionKind::Chronic)
+                .fold(1.0, |f, c| {
+                    f * (1.0 - c.severity * config.fertility_penalty)
+                })
+                .max(0.0);
+        }
+
+        pub fn pregnancy(self: &Self) -> Option<&Condition> {
+            return self
+                .conditions
+                .iter()
+                .find(|c| matches!(c.kind, ConditionKind::Pregnancy { .. }));
+        }
+
+        // the child is born the year after it is conceived
+        pub fn conceive(self: &mut Self, other_parent: &MindId, year: usize) {
+            self.conditions.push(Condition {
+                kind: ConditionKind::Pregnancy {
+                    other_parent: other_parent.clone(),
+                },
+                name: String::from("a pregnancy"),
+                severity: 0.0,
+                since: year,
+                until: Some(year + 1),
+            });
+        }
+
+        pub fn end_pregnancy(self: &mut Self) {
+            self.conditions
+                .retain(|c| !matches!(c.kind, ConditionKind::Pregnancy { .. }));
+        }
+
+        pub fn infection(self: &Self) -> Option<&Condition> {
+            return self
+                .conditions
+                .iter()
+                .find(|c| c.kind.eq(&ConditionKind::Infection));
+        }
+
+        pub fn describe(self: &Self) -> Option<String> {
+            if self.conditions.len() == 0 {
+                return None;
+            }
+            let names: Vec<String> = self.conditions.iter().map(|c| c.to_string()).collect();
+            return Some(render_list(names.iter().map(|n| n.as_str()).collect()));
+        }
+
+        // a year of injuries healing, chronic conditions worsening or being cured, and new
+        // conditions starting, older minds fall ill more often
+        fn progress(
+            self: &Self,
+            config: &HealthConfig,
+            age: u32,
+            avg_lifespan: u32,
+            year: usize,
+            rng: &mut StdRng,
+        ) -> Health {
+            let cared = self.cared_for_by.is_some();
+            let mut conditions: Vec<Condition> = Vec::new();
+            for condition in &self.conditions {
+                let mut condition = condition.clone();
+                match condition.kind {
+                    ConditionKind::Injury => {
+                        if condition.until.is_some_and(|until| until <= year) {
+                            if rng.gen::<f32>() >= config.disability_chance * condition.severity {
+                                continue;
+                            }
+                            condition.kind = ConditionKind::Disability;
+                            condition.name = DISABILITIES.choose(rng).unwrap().to_string();
+                            condition.since = year;
+                            condition.until = None;
+                        }
+                    }
+                    ConditionKind::Chronic => {
+                        if cared && rng.gen::<f32>() < config.cure_chance {
+                            continue;
+                        }
+                        if !cared {
+                            condition.severity = (condition.severity + config.progression).min(1.0);
+                        }
+                    }
+                    // epidemics run their own course
+                    ConditionKind::Disability
+                    | ConditionKind::Pregnancy { .. }
+                    | ConditionKind::Infection => {}
+                }
+                conditions.push(condition);
+            }
+            let age_factor = age as f32 / avg_lifespan.max(1) as f32;
+            if rng.gen::<f32>() < config.chronic_rate * age_factor {
+                conditions.push(Condition {
+                    kind: ConditionKind::Chronic,
+                    name: CHRONIC_CONDITIONS.choose(rng).unwrap().to_string(),
+                    severity: rng.gen_range(0.1..0.6),
+                    since: year,
+                    until: None,
+                });
+            }
+            if rng.gen::<f32>() < config.injury_rate {
+                conditions.push(Condition {
+                    kind: ConditionKind::Injury,
+                    name: INJURIES.choose(rng).unwrap().to_string(),
+                    severity: rng.gen_range(0.1..1.0),
+                    since: year,
+                    until: Some(year + rng.gen_range(1..=3)),
+                });
+            }
+            return Health {
+                conditions,
+                cared_for_by: self.cared_for_by,
+            };
+        }
+    }
+
+    impl City {
+        pub fn update_health(self: &mut Self, rng: &mut StdRng) {
+            let config = &self.config.health;
+            let population = &self.population;
+            let (culture, minorities) = (&self.culture, &self.minorities);
+            let year = self.year;
+            let updates =
+                propose_for_minds(&self.current_citizens(), phase_seed(rng), |id, rng| {
+                    let mind = population.get(id).unwrap();
+                    let culture = minorities.get(&mind.culture).unwrap_or(culture);
+                    return (
+                        id.clone(),
+                        mind.health
+                            .progress(config, mind.age, culture.avg_lifespan, year, rng),
+                    );
+                });
+            for (id, health) in updates {
+                self.population.get_mut(&id).unwrap().health = health;
+            }
+            self.assign_care();
+            self.add_timestamp("update health");
+        }
+
+        fn care_capacity(self: &Self, institution_id: &InstitutionId) -> usize {
+            let institution = self.institutions.get(institution_id);
+            if institution.is_none() || !institution.unwrap().provides_care {
+                return 0;
+            }
+            return institution.unwrap().staff.len() * self.config.health.patients_per_staff;
+        }
+
+        // how many of the sick and injured no institution has room to care for
+        pub fn care_shortfall(self: &Self) -> usize {
+            let patients = self
+                .population
+                .values()
+                .filter(|m| m.alive && m.health.needs_care())
+                .count();
+            let capacity: usize = self
+                .institutions
+                .keys()
+                .map(|id| self.care_capacity(id))
+                .sum();
+            return patients.saturating_sub(capacity);
+        }
+
+        pub fn patients_of(self: &Self, institution_id: &InstitutionId) -> Vec<MindId> {
+            return self
+                .population
+                .values()
+                .filter(|m| m.alive && m.health.cared_for_by.eq(&Some(institution_id.clone())))
+                .map(|m| m.id.clone())
+                .collect();
+        }
+
+        // the most severely ill are taken in first, wherever there is room
+        fn assign_care(self: &mut Self) {
+            let mut patients: Vec<(f32, MindId)> = Vec::new();
+            for mind in self.population.values_mut() {
+                mind.health.cared_for_by = None;
+                if mind.alive && mind.health.needs_care() {
+                    let severity: f32 = mind.health.conditions.iter().map(|c| c.severity).sum();
+                    patients.push((severity, mind.id.clone()));
+                }
+            }
+            patients.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+            let mut beds: Vec<(InstitutionId, usize)> = self
+                .institutions
+                .keys()
+                .map(|id| (id.clone(), self.care_capacity(id)))
+                .filter(|(_, capacity)| *capacity > 0)
+                .collect();
+            for (_, m_id) in patients {
+                let bed = beds.iter_mut().find(|(_, capacity)| *capacity > 0);
+                if bed.is_none() {
+                    break;
+                }
+                let (institution_id, capacity) = bed.unwrap();
+                *capacity -= 1;
+                self.population.get_mut(&m_id).unwrap().health.cared_for_by =
+                    Some(institution_id.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn test_health_affects_work_fertility_and_care() {
+        use crate::city::{
+            city::{random_city, Era},
+            config::config::SimulationConfig,
+        };
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let config = HealthConfig::default();
+        let mut health = Health::default();
+        health.conditions.push(Condition {
+            kind: ConditionKind::Chronic,
+            name: String::from("gout"),
+            severity: 0.5,
+            since: 0,
+            until: None,
+        });
+        assert!(health.fertility(&config) < 1.0);
+        let uncared = health.illness_chance(&config);
+        health.cared_for_by = Some(uuid::Uuid::nil());
+        assert!(health.illness_chance(&config) < uncared);
+        assert!(health.can_work(&config));
+        health.conditions.push(Condition {
+            kind: ConditionKind::Disability,
+            name: String::from("blindness"),
+            severity: 1.0,
+            since: 0,
+            until: None,
+        });
+        assert!(!health.can_work(&config));
+
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut config = SimulationConfig::default();
+        config.health.chronic_rate = 0.2;
+        config.institutions.healthcare_ratio = 50.0;
+        let mut city = random_city(&dict, &templates, &config, Era::Medieval, 150, 21);
+        for _i in 0..30 {
+            city.simulate_year(&dict);
+        }
+        let hospitals: Vec<InstitutionId> = city
+            .institutions
+            .values()
+            .filter(|i| i.provides_care)
+            .map(|i| i.id.clone())
+            .collect();
+        assert!(hospitals.len() > 0);
+        // staff hired and lost since the health update change the room there is
+        city.assign_care();
+        for id in hospitals {
+            assert!(city.patients_of(&id).len() <= city.care_capacity(&id));
+        }
+        for mind in city.population.values() {
+            if mind.health.cared_for_by.is_some() {
+                assert!(mind.alive && mind.health.needs_care());
+            }
+        }
+    }
+}
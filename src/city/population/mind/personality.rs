@@ -1,16 +1,17 @@
 pub mod personality {
-    use rand::Rng;
-    use std::{collections::HashSet, fmt};
+    use rand::{rngs::StdRng, Rng};
+    use serde::{Deserialize, Serialize};
+    use std::{collections::BTreeSet, fmt};
 
     use crate::city::culture::culture::Culture;
 
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct Personality {
         pub matrix: PersonalityMatrix,
-        pub traits: HashSet<PersonalityTrait>,
+        pub traits: BTreeSet<PersonalityTrait>,
         pub thiest: bool,
     }
-    #[derive(PartialEq, Debug, Clone)]
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct PersonalityMatrix {
         // athiest <-> thiest
         pub spirituality: f32,
@@ -60,7 +61,7 @@ pub mod personality {
         }
     }
 
-    #[derive(PartialEq, Debug, Clone, Hash, Eq)]
+    #[derive(PartialEq, Debug, Clone, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     pub enum PersonalityTrait {
         // Pure Spirituality
         Devout,
@@ -96,8 +97,7 @@ pub mod personality {
         Intellectual,
     }
 
-    pub fn random_personality(culture: &Culture) -> Personality {
-        let mut rng = rand::thread_rng();
+    pub fn random_personality(culture: &Culture, rng: &mut StdRng) -> Personality {
         let mut output = Personality {
             matrix: PersonalityMatrix {
                 spirituality: rng.gen::<f32>(),
@@ -108,7 +108,7 @@ pub mod personality {
                 conscientiousness: rng.gen::<f32>(),
                 experience_openness: rng.gen::<f32>(),
             },
-            traits: HashSet::new(),
+            traits: BTreeSet::new(),
             thiest: false,
         };
         let mut roll = |t: f32| rng.gen::<f32>() < t;
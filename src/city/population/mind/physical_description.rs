@@ -1,8 +1,11 @@
 pub mod physical_description {
     use procgen_templater::dictionary::{dictionary::Dictionary, word::word::WordType};
-    use rand::Rng;
+    use rand::{rngs::StdRng, Rng};
+    use serde::{Deserialize, Serialize};
 
-    #[derive(PartialEq, Debug, Clone)]
+    use crate::seeded::seeded::random_word;
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
     pub struct PhysicalDescription {
         pub hair_colour: String,
         pub hair_style: String,
@@ -33,26 +36,29 @@ pub mod physical_description {
         }
     }
 
-    fn random_descriptor(dict: &Dictionary, tag: String) -> String {
-        return dict
-            .get_random_word((
+    fn random_descriptor(dict: &Dictionary, tag: String, rng: &mut StdRng) -> String {
+        return random_word(
+            dict,
+            (
                 WordType::Adjective,
                 vec![vec![tag], vec!["Personal".to_string()]],
-            ))
-            .unwrap()
-            .base
-            .to_ascii_lowercase()
-            .to_string();
+            ),
+            rng,
+        )
+        .unwrap()
+        .base
+        .to_ascii_lowercase()
+        .to_string();
     }
 
-    pub fn random_description(dict: &Dictionary) -> PhysicalDescription {
+    pub fn random_description(dict: &Dictionary, rng: &mut StdRng) -> PhysicalDescription {
         return PhysicalDescription {
-            hair_colour: random_descriptor(dict, "HairColour".to_string()),
-            hair_style: random_descriptor(dict, "HairStyle".to_string()),
-            hair_adjective: random_descriptor(dict, "HairState".to_string()),
-            eye_colour: random_descriptor(dict, "EyeColour".to_string()),
-            height_adjective: random_descriptor(dict, "Height".to_string()),
-            build_adjective: random_descriptor(dict, "Build".to_string()),
+            hair_colour: random_descriptor(dict, "HairColour".to_string(), rng),
+            hair_style: random_descriptor(dict, "HairStyle".to_string(), rng),
+            hair_adjective: random_descriptor(dict, "HairState".to_string(), rng),
+            eye_colour: random_descriptor(dict, "EyeColour".to_string(), rng),
+            height_adjective: random_descriptor(dict, "Height".to_string(), rng),
+            build_adjective: random_descriptor(dict, "Build".to_string(), rng),
         };
     }
 
@@ -61,15 +67,15 @@ pub mod physical_description {
         tag: String,
         a1: &String,
         a2: &String,
+        rng: &mut StdRng,
     ) -> String {
-        let mut rng = rand::thread_rng();
         let roll = rng.gen::<f32>();
         if roll < 0.45 {
             return a1.clone();
         } else if roll < 0.9 {
             return a2.clone();
         } else {
-            return random_descriptor(dict, tag);
+            return random_descriptor(dict, tag, rng);
         }
     }
 
@@ -77,6 +83,7 @@ pub mod physical_description {
         dict: &Dictionary,
         p1: &PhysicalDescription,
         p2: &PhysicalDescription,
+        rng: &mut StdRng,
     ) -> PhysicalDescription {
         return PhysicalDescription {
             hair_colour: choose_or_mutate_attribute(
@@ -84,31 +91,36 @@ pub mod physical_description {
                 "HairColour".to_string(),
                 &p1.hair_colour,
                 &p2.hair_colour,
+                rng,
             ),
-            hair_style: random_descriptor(dict, "HairStyle".to_string()),
+            hair_style: random_descriptor(dict, "HairStyle".to_string(), rng),
             hair_adjective: choose_or_mutate_attribute(
                 &dict,
                 "HairState".to_string(),
                 &p1.hair_adjective,
                 &p2.hair_adjective,
+                rng,
             ),
             eye_colour: choose_or_mutate_attribute(
                 &dict,
                 "EyeColour".to_string(),
                 &p1.eye_colour,
                 &p2.eye_colour,
+                rng,
             ),
             height_adjective: choose_or_mutate_attribute(
                 &dict,
                 "Height".to_string(),
                 &p1.height_adjective,
                 &p2.height_adjective,
+                rng,
             ),
             build_adjective: choose_or_mutate_attribute(
                 &dict,
                 "Build".to_string(),
                 &p1.build_adjective,
                 &p2.build_adjective,
+                rng,
             ),
         };
     }
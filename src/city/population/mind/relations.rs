@@ -2,13 +2,14 @@ pub mod children;
 pub mod friends;
 pub mod partners;
 pub mod relations {
-    use std::{collections::HashSet, fmt};
+    use serde::{Deserialize, Serialize};
+    use std::{collections::BTreeSet, fmt};
 
     use uuid::Uuid;
 
     use crate::city::city::City;
 
-    #[derive(PartialEq, Debug, Clone, Eq, Hash)]
+    #[derive(PartialEq, Debug, Clone, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
     pub enum RelationVerb {
         // family
         Parent,
@@ -70,12 +71,14 @@ pub mod relations {
 
     impl City {
         pub fn generate_family_relations(self: &mut Self, target_id: &Uuid) {
-            let reference_population = self.population.clone();
-            let target = reference_population.get(target_id).unwrap();
-            let mut relations_to_add: HashSet<(Uuid, RelationVerb)> = HashSet::new();
-            let parents = target.get_relations(RelationVerb::Parent);
+            let mut relations_to_add: BTreeSet<(Uuid, RelationVerb)> = BTreeSet::new();
+            let parents = self
+                .population
+                .get(target_id)
+                .unwrap()
+                .get_relations(RelationVerb::Parent);
             for p_id in parents {
-                let parent = reference_population.get(&p_id).unwrap();
+                let parent = self.population.get(&p_id).unwrap().clone();
                 // Siblings
                 let siblings = parent.get_relations(RelationVerb::Child);
                 for sibling_id in siblings {
@@ -84,7 +87,7 @@ pub mod relations {
                         if !sibling.relations.contains_key(&RelationVerb::Sibling) {
                             sibling
                                 .relations
-                                .insert(RelationVerb::Sibling, HashSet::new());
+                                .insert(RelationVerb::Sibling, BTreeSet::new());
                         }
                         sibling
                             .relations
@@ -104,7 +107,7 @@ pub mod relations {
                     {
                         grandparent
                             .relations
-                            .insert(RelationVerb::Grandchild, HashSet::new());
+                            .insert(RelationVerb::Grandchild, BTreeSet::new());
                     }
                     grandparent
                         .relations
@@ -120,7 +123,7 @@ pub mod relations {
                     if !pibling.relations.contains_key(&RelationVerb::Nibling) {
                         pibling
                             .relations
-                            .insert(RelationVerb::Nibling, HashSet::new());
+                            .insert(RelationVerb::Nibling, BTreeSet::new());
                     }
                     pibling
                         .relations
@@ -136,7 +139,7 @@ pub mod relations {
                     if !cousin.relations.contains_key(&RelationVerb::Cousin) {
                         cousin
                             .relations
-                            .insert(RelationVerb::Cousin, HashSet::new());
+                            .insert(RelationVerb::Cousin, BTreeSet::new());
                     }
                     cousin
                         .relations
@@ -149,7 +152,7 @@ pub mod relations {
             let target_mut = self.population.get_mut(target_id).unwrap();
             for (r_id, verb) in relations_to_add {
                 if !target_mut.relations.contains_key(&verb) {
-                    target_mut.relations.insert(verb.clone(), HashSet::new());
+                    target_mut.relations.insert(verb.clone(), BTreeSet::new());
                 }
                 target_mut.relations.get_mut(&verb).unwrap().insert(r_id);
             }
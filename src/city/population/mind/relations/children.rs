@@ -1,135 +1,212 @@
 pub mod children {
-    use std::collections::HashSet;
+    use std::collections::BTreeSet;
 
     use procgen_templater::dictionary::dictionary::Dictionary;
-    use rand::{seq::SliceRandom, Rng};
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
     use uuid::Uuid;
 
     use crate::city::{
+        chronicle::chronicle::Event,
         city::City,
         population::mind::{
-            mind::{random_mind, Gender},
+            health::health::ConditionKind,
+            mind::{random_mind, CauseOfDeath, Gender, MindId},
             physical_description::physical_description::merge_descriptions,
             relations::relations::RelationVerb,
         },
     };
 
-    const CHILD_MAX: usize = 5;
-    const PARTNER_CHILD_ANNUAL_CHANCE: f32 = 0.1;
-    const SPOUSE_CHILD_ANNUAL_CHANCE: f32 = 0.2;
-
     impl City {
-        pub fn generate_children(self: &mut Self, dict: &Dictionary) {
-            let population_ref = self.population.clone();
-            let mut included: HashSet<&Uuid> = HashSet::new();
-            let mut partners: HashSet<(&Uuid, &Uuid, RelationVerb)> = HashSet::new();
-            for mind in population_ref.values() {
-                if !included.contains(&mind.id) {
+        pub fn generate_children(self: &mut Self, dict: &Dictionary, rng: &mut StdRng) {
+            // last year's pregnancies come to term
+            let due: Vec<(MindId, MindId)> = self
+                .population
+                .values()
+                .filter(|m| m.alive)
+                .filter_map(|m| {
+                    let pregnancy = m.health.pregnancy()?;
+                    if !pregnancy.until.is_some_and(|until| until <= self.year) {
+                        return None;
+                    }
+                    return match &pregnancy.kind {
+                        ConditionKind::Pregnancy { other_parent } => {
+                            Some((m.id.clone(), other_parent.clone()))
+                        }
+                        _ => None,
+                    };
+                })
+                .collect();
+            let childbirth_chance = self.config.mortality.childbirth_mortality
+                * self.config.mortality.medicine(&self.culture.era);
+            for (mother, other_parent) in due {
+                self.population
+                    .get_mut(&mother)
+                    .unwrap()
+                    .health
+                    .end_pregnancy();
+                self.birth_child(dict, &mother, &other_parent, rng);
+                // a mother can die giving birth, as medicine allows
+                if rng.gen::<f32>() < childbirth_chance {
+                    self.kill_mind(&mother, CauseOfDeath::Childbirth);
+                }
+            }
+
+            let mut included: BTreeSet<Uuid> = BTreeSet::new();
+            let mut partners: BTreeSet<(Uuid, Uuid, RelationVerb)> = BTreeSet::new();
+            for mind in self.population.values() {
+                if mind.alive && !included.contains(&mind.id) {
                     for (verb, ids) in &mind.relations {
                         if vec![RelationVerb::Partner, RelationVerb::Spouse].contains(&verb) {
                             for r_id in ids {
                                 if !included.contains(r_id) {
-                                    included.insert(r_id);
-                                    included.insert(&mind.id);
-                                    partners.insert((&mind.id, r_id, verb.clone()));
+                                    included.insert(r_id.clone());
+                                    included.insert(mind.id.clone());
+                                    partners.insert((mind.id.clone(), r_id.clone(), verb.clone()));
                                 }
                             }
                         }
                     }
                 }
             }
-            let mut rng = rand::thread_rng();
+            let config = self.config.children.clone();
             for (m1_id, m2_id, relation) in partners {
-                let mind_1 = population_ref.get(m1_id).unwrap();
-                let mind_2 = population_ref.get(m2_id).unwrap();
-                let child_total = mind_1
-                    .relations
-                    .get(&RelationVerb::Child)
-                    .unwrap_or(&HashSet::new())
-                    .len()
-                    + mind_2
-                        .relations
-                        .get(&RelationVerb::Child)
-                        .unwrap_or(&HashSet::new())
-                        .len();
+                let mind_1 = self.population.get(&m1_id).unwrap();
+                let mind_2 = self.population.get(&m2_id).unwrap();
+                // families make up for the children they lose, a pregnancy counts as a child
+                let child_total = [mind_1, mind_2]
+                    .iter()
+                    .flat_map(|m| m.get_relations(RelationVerb::Child))
+                    .filter(|c_id| self.population.get(c_id).unwrap().alive)
+                    .count()
+                    + [mind_1, mind_2]
+                        .iter()
+                        .filter(|m| m.health.pregnancy().is_some())
+                        .count();
+                let fertility = mind_1.health.fertility(&self.config.health)
+                    * mind_2.health.fertility(&self.config.health);
                 let roll = rng.gen::<f32>();
                 let roll_target = if relation.eq(&RelationVerb::Spouse) {
-                    SPOUSE_CHILD_ANNUAL_CHANCE
+                    config.spouse_child_annual_chance
                 } else {
-                    PARTNER_CHILD_ANNUAL_CHANCE
+                    config.partner_child_annual_chance
                 };
-                if child_total < CHILD_MAX
+                if child_total < config.child_max
+                    && mind_1.alive
+                    && mind_2.alive
                     && mind_1.age < 50
                     && mind_2.age < 50
-                    && roll < roll_target
+                    && roll < roll_target * fertility
                 {
-                    let mut child = random_mind(dict, &self.culture, self.year as i32);
-                    child.year_of_birth = self.year as i32;
-                    child.description =
-                        merge_descriptions(&dict, &mind_1.description, &mind_2.description);
-                    child.age = 0;
-
-                    let mut surname_formats = self.culture.child_surname_formats.clone();
-                    surname_formats.shuffle(&mut rng);
-                    let formats = surname_formats.first().unwrap();
-                    let format = if child.gender.eq(&Gender::Male) {
-                        formats.0.clone()
-                    } else if child.gender.eq(&Gender::Female) {
-                        formats.1.clone()
-                    } else if rng.gen::<f32>() < 0.5 {
-                        formats.0.clone()
+                    // a mother carries the child until next year, other couples have one
+                    // straight away
+                    let mother = [&m1_id, &m2_id]
+                        .into_iter()
+                        .find(|id| self.population.get(id).unwrap().gender.eq(&Gender::Female))
+                        .cloned();
+                    if mother.is_some() {
+                        let mother = mother.unwrap();
+                        let other_parent = if mother.eq(&m1_id) { &m2_id } else { &m1_id };
+                        let year = self.year;
+                        self.population
+                            .get_mut(&mother)
+                            .unwrap()
+                            .health
+                            .conceive(other_parent, year);
                     } else {
-                        formats.1.clone()
-                    };
-                    child.last_name = format.render(
-                        mind_1.first_name.clone(),
-                        mind_1.last_name.clone(),
-                        mind_2.first_name.clone(),
-                        mind_2.last_name.clone(),
-                    );
+                        self.birth_child(dict, &m1_id, &m2_id, rng);
+                    }
+                }
+            }
+            self.add_timestamp("generate children");
+        }
 
-                    child.relations.insert(RelationVerb::Parent, HashSet::new());
-                    child
-                        .relations
-                        .get_mut(&RelationVerb::Parent)
-                        .unwrap()
-                        .insert(mind_1.id.clone());
-                    child
-                        .relations
-                        .get_mut(&RelationVerb::Parent)
-                        .unwrap()
-                        .insert(mind_2.id.clone());
+        fn birth_child(
+            self: &mut Self,
+            dict: &Dictionary,
+            parent_1: &MindId,
+            parent_2: &MindId,
+            rng: &mut StdRng,
+        ) -> MindId {
+            let mind_1 = self.population.get(parent_1).unwrap();
+            let mind_2 = self.population.get(parent_2).unwrap();
+            // children of parents from different cultures are raised in one of them
+            let culture_id = if mind_1.culture.eq(&mind_2.culture) || rng.gen::<f32>() < 0.5 {
+                mind_1.culture.clone()
+            } else {
+                mind_2.culture.clone()
+            };
+            let culture = self.culture_by_id(&culture_id);
+            let mut child = random_mind(
+                self.dictionary_for(dict, &culture_id),
+                culture,
+                &self.config.demographics,
+                self.year as i32,
+                rng,
+            );
+            child.year_of_birth = self.year as i32;
+            child.description =
+                merge_descriptions(&dict, &mind_1.description, &mind_2.description, rng);
+            child.age = 0;
 
-                    let mind_1_mut = self.population.get_mut(&mind_1.id).unwrap();
-                    if !mind_1_mut.relations.contains_key(&RelationVerb::Child) {
-                        mind_1_mut
-                            .relations
-                            .insert(RelationVerb::Child, HashSet::new());
-                    }
-                    mind_1_mut
-                        .relations
-                        .get_mut(&RelationVerb::Child)
-                        .unwrap()
-                        .insert(child.id.clone());
+            let mut surname_formats = culture.child_surname_formats.clone();
+            surname_formats.shuffle(rng);
+            let formats = surname_formats.first().unwrap();
+            let format = if child.gender.eq(&Gender::Male) {
+                formats.0.clone()
+            } else if child.gender.eq(&Gender::Female) {
+                formats.1.clone()
+            } else if rng.gen::<f32>() < 0.5 {
+                formats.0.clone()
+            } else {
+                formats.1.clone()
+            };
+            child.last_name = format.render(
+                mind_1.first_name.clone(),
+                mind_1.last_name.clone(),
+                mind_2.first_name.clone(),
+                mind_2.last_name.clone(),
+                rng,
+            );
+            child.origional_last_name = child.last_name.clone();
 
-                    let mind_2_mut = self.population.get_mut(&mind_2.id).unwrap();
-                    if !mind_2_mut.relations.contains_key(&RelationVerb::Child) {
-                        mind_2_mut
-                            .relations
-                            .insert(RelationVerb::Child, HashSet::new());
-                    }
-                    mind_2_mut
-                        .relations
-                        .get_mut(&RelationVerb::Child)
-                        .unwrap()
-                        .insert(child.id.clone());
+            child
+                .relations
+                .insert(RelationVerb::Parent, BTreeSet::new());
+            child
+                .relations
+                .get_mut(&RelationVerb::Parent)
+                .unwrap()
+                .insert(parent_1.clone());
+            child
+                .relations
+                .get_mut(&RelationVerb::Parent)
+                .unwrap()
+                .insert(parent_2.clone());
 
-                    let c_id = child.id.clone();
-                    self.population.insert(child.id.clone(), child);
-                    self.generate_family_relations(&c_id);
+            for parent_id in [parent_1, parent_2] {
+                let parent_mut = self.population.get_mut(parent_id).unwrap();
+                if !parent_mut.relations.contains_key(&RelationVerb::Child) {
+                    parent_mut
+                        .relations
+                        .insert(RelationVerb::Child, BTreeSet::new());
                 }
+                parent_mut
+                    .relations
+                    .get_mut(&RelationVerb::Child)
+                    .unwrap()
+                    .insert(child.id.clone());
             }
-            self.add_timestamp("generate children");
+
+            let c_id = child.id.clone();
+            self.record_event(Event::Birth {
+                year: self.year,
+                child: c_id.clone(),
+                parents: vec![parent_1.clone(), parent_2.clone()],
+            });
+            self.population.insert(child.id.clone(), child);
+            self.generate_family_relations(&c_id);
+            self.settle_mind(dict, &c_id, vec![parent_1.clone(), parent_2.clone()], rng);
+            return c_id;
         }
     }
 }
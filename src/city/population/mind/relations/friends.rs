@@ -1,11 +1,12 @@
 pub mod friends {
-    use std::collections::{HashMap, HashSet};
+    use std::collections::{BTreeMap, BTreeSet};
 
     use crate::city::{
         city::City,
+        parallel::parallel::{phase_seed, propose_for_minds},
         population::mind::{mind::MindId, relations::relations::RelationVerb},
     };
-    use rand::{seq::SliceRandom, Rng};
+    use rand::{rngs::StdRng, Rng};
     use uuid::Uuid;
 
     pub const SOCIAL_RELATIONS: [RelationVerb; 3] = [
@@ -28,223 +29,241 @@ pub mod friends {
         RelationVerb::Nibling,
     ];
 
-    fn generate_age_cache(city: &City) -> HashMap<u32, HashSet<Uuid>> {
-        let mut cache: HashMap<u32, HashSet<Uuid>> = HashMap::new();
-        for mind in city.population.values() {
+    // draws that land on an excluded mind are retried this many times before giving up
+    const FRIEND_DRAW_ATTEMPTS: usize = 5;
+
+    fn generate_age_cache(city: &City) -> BTreeMap<u32, Vec<Uuid>> {
+        let mut cache: BTreeMap<u32, Vec<Uuid>> = BTreeMap::new();
+        for mind in city.population.values().filter(|m| m.alive) {
             if cache.contains_key(&mind.age) {
-                cache.get_mut(&mind.age).unwrap().insert(mind.id.clone());
+                cache.get_mut(&mind.age).unwrap().push(mind.id.clone());
             } else {
-                let mut i: HashSet<Uuid> = HashSet::new();
-                i.insert(mind.id.clone());
-                cache.insert(mind.age, i);
+                cache.insert(mind.age, vec![mind.id.clone()]);
             }
         }
         return cache;
     }
 
-    fn filter_to_social_relations(
-        r: &HashMap<RelationVerb, HashSet<Uuid>>,
-    ) -> HashMap<Uuid, HashSet<RelationVerb>> {
-        // return r
-        //     .iter()
-        //     .filter(|(_id, verb_set)| verb_set.iter().any(|v| SOCIAL_RELATIONS.contains(&v)))
-        //     .map(|(i, j)| (i.clone(), j.clone()))
-        //     .collect();
-        let mut output: HashMap<Uuid, HashSet<RelationVerb>> = HashMap::new();
+    fn filter_to_social_relations(r: &BTreeMap<RelationVerb, BTreeSet<Uuid>>) -> BTreeSet<Uuid> {
+        let mut output: BTreeSet<Uuid> = BTreeSet::new();
         for (verb, ids) in r {
             if SOCIAL_RELATIONS.contains(verb) {
-                for id in ids {
-                    if !output.contains_key(id) {
-                        output.insert(id.clone(), HashSet::new());
-                    }
-                    output.get_mut(id).unwrap().insert(verb.clone());
-                }
+                output.extend(ids.iter().cloned());
             }
         }
         return output;
     }
 
     fn filter_to_friend_exclusion_list(
-        r: &HashMap<RelationVerb, HashSet<Uuid>>,
-    ) -> HashMap<Uuid, HashSet<RelationVerb>> {
-        // return r
-        //     .iter()
-        //     .filter(|(_id, verb_set)| verb_set.iter().any(|v| FRIEND_EXCLUSIONS.contains(&v)))
-        //     .map(|(i, j)| (i.clone(), j.clone()))
-        //     .collect();
-        let mut output: HashMap<Uuid, HashSet<RelationVerb>> = HashMap::new();
+        r: &BTreeMap<RelationVerb, BTreeSet<Uuid>>,
+    ) -> BTreeSet<Uuid> {
+        let mut output: BTreeSet<Uuid> = BTreeSet::new();
         for (verb, ids) in r {
             if FRIEND_EXCLUSIONS.contains(verb) {
-                for id in ids {
-                    if !output.contains_key(id) {
-                        output.insert(id.clone(), HashSet::new());
-                    }
-                    output.get_mut(id).unwrap().insert(verb.clone());
-                }
+                output.extend(ids.iter().cloned());
             }
         }
         return output;
     }
 
-    fn temp_generate_eligible_friend_list(
-        cache: &HashMap<u32, HashSet<Uuid>>,
+    fn temp_eligible_friend_buckets(
+        cache: &BTreeMap<u32, Vec<Uuid>>,
         min_age: u32,
         max_age: u32,
-        exclude: &HashSet<&Uuid>,
-    ) -> Vec<Uuid> {
-        let mut output: Vec<Uuid> = Vec::new();
-        for i in min_age..max_age {
-            if cache.contains_key(&i) {
-                let set = cache.get(&i).unwrap();
-                for j in set {
-                    if !exclude.contains(&j) {
-                        output.push(j.clone());
+    ) -> Vec<&Vec<Uuid>> {
+        if min_age >= max_age {
+            return Vec::new();
+        }
+        return cache
+            .range(min_age..max_age)
+            .map(|(_age, ids)| ids)
+            .collect();
+    }
+
+    // picks uniformly from the buckets without collecting them, skipping excluded minds
+    fn temp_draw_eligible_friend(
+        buckets: &Vec<&Vec<Uuid>>,
+        exclude: &BTreeSet<Uuid>,
+        rng: &mut StdRng,
+    ) -> Option<Uuid> {
+        let total: usize = buckets.iter().map(|b| b.len()).sum();
+        if total == 0 {
+            return None;
+        }
+        for _attempt in 0..FRIEND_DRAW_ATTEMPTS {
+            let mut index = rng.gen_range(0..total);
+            for bucket in buckets {
+                if index < bucket.len() {
+                    if !exclude.contains(&bucket[index]) {
+                        return Some(bucket[index].clone());
                     }
+                    break;
                 }
+                index -= bucket.len();
             }
         }
-        output.shuffle(&mut rand::thread_rng());
-        return output;
+        return None;
     }
 
-    fn temp_friend_evolution<'a>(city: &'a mut City, mind_id: &MindId) -> &'a mut City {
-        let mut rng = rand::thread_rng();
-        let mind_ref = city.population.get(mind_id).unwrap().clone();
-        for (verb, target_ids) in &mind_ref.relations {
-            for target_id in target_ids {
-                let mut to_remove: Option<RelationVerb> = None;
-                let mut to_add: Option<RelationVerb> = None;
-
-                if verb.eq(&RelationVerb::Acquaintance) {
-                    if rng.gen::<f32>() < 0.6 {
-                        to_remove = Some(RelationVerb::Acquaintance);
-                    } else if rng.gen::<f32>() < 0.25 {
-                        to_remove = Some(RelationVerb::Acquaintance);
-                        to_add = Some(RelationVerb::Friend);
-                    }
-                } else if verb.eq(&RelationVerb::Friend) {
-                    if rng.gen::<f32>() < 0.25 {
-                        to_remove = Some(RelationVerb::Friend);
-                        to_add = Some(RelationVerb::Acquaintance);
-                    } else if rng.gen::<f32>() < 0.125 {
-                        to_remove = Some(RelationVerb::Friend);
-                        to_add = Some(RelationVerb::CloseFriend);
-                    }
-                } else if verb.eq(&RelationVerb::CloseFriend) {
-                    if rng.gen::<f32>() < 0.125 {
-                        to_remove = Some(RelationVerb::CloseFriend);
-                        to_add = Some(RelationVerb::Friend);
-                    }
+    fn temp_friend_evolution<'a>(
+        city: &'a mut City,
+        mind_id: &MindId,
+        rng: &mut StdRng,
+    ) -> &'a mut City {
+        let social: Vec<(RelationVerb, Uuid)> = city
+            .population
+            .get(mind_id)
+            .unwrap()
+            .relations
+            .iter()
+            .filter(|(verb, _ids)| SOCIAL_RELATIONS.contains(verb))
+            .map(|(verb, ids)| ids.iter().map(|id| (verb.clone(), id.clone())))
+            .flatten()
+            .collect();
+        let config = city.config.friends.clone();
+        for (verb, target_id) in &social {
+            let mut to_remove: Option<RelationVerb> = None;
+            let mut to_add: Option<RelationVerb> = None;
+
+            if verb.eq(&RelationVerb::Acquaintance) {
+                if rng.gen::<f32>() < config.acquaintance_lost_chance {
+                    to_remove = Some(RelationVerb::Acquaintance);
+                } else if rng.gen::<f32>() < config.acquaintance_to_friend_chance {
+                    to_remove = Some(RelationVerb::Acquaintance);
+                    to_add = Some(RelationVerb::Friend);
+                }
+            } else if verb.eq(&RelationVerb::Friend) {
+                if rng.gen::<f32>() < config.friend_to_acquaintance_chance {
+                    to_remove = Some(RelationVerb::Friend);
+                    to_add = Some(RelationVerb::Acquaintance);
+                } else if rng.gen::<f32>() < config.friend_to_close_friend_chance {
+                    to_remove = Some(RelationVerb::Friend);
+                    to_add = Some(RelationVerb::CloseFriend);
+                }
+            } else if verb.eq(&RelationVerb::CloseFriend) {
+                if rng.gen::<f32>() < config.close_friend_to_friend_chance {
+                    to_remove = Some(RelationVerb::CloseFriend);
+                    to_add = Some(RelationVerb::Friend);
                 }
+            }
 
-                if to_remove.is_some() {
-                    let remove = to_remove.unwrap();
-                    let mind = city.population.get_mut(&mind_ref.id).unwrap();
-                    mind.relations.get_mut(&remove).unwrap().remove(target_id);
-                    let target = city.population.get_mut(target_id).unwrap();
-                    target
-                        .relations
-                        .get_mut(&remove)
-                        .unwrap()
-                        .remove(&mind_ref.id);
+            if to_remove.is_some() {
+                let remove = to_remove.unwrap();
+                let mind = city.population.get_mut(mind_id).unwrap();
+                mind.relations.get_mut(&remove).unwrap().remove(target_id);
+                let target = city.population.get_mut(target_id).unwrap();
+                target.relations.get_mut(&remove).unwrap().remove(mind_id);
+            }
+            if to_add.is_some() {
+                let add = to_add.unwrap();
+                let mind = city.population.get_mut(mind_id).unwrap();
+                if !mind.relations.contains_key(&add) {
+                    mind.relations.insert(add.clone(), BTreeSet::new());
                 }
-                if to_add.is_some() {
-                    let add = to_add.unwrap();
-                    let mind = city.population.get_mut(&mind_ref.id).unwrap();
-                    if !mind.relations.contains_key(&add) {
-                        mind.relations.insert(add.clone(), HashSet::new());
-                    }
-                    mind.relations
-                        .get_mut(&add)
-                        .unwrap()
-                        .insert(target_id.clone());
-                    let target = city.population.get_mut(target_id).unwrap();
-                    if !target.relations.contains_key(&add) {
-                        target.relations.insert(add.clone(), HashSet::new());
-                    }
-                    target.relations.get_mut(&add).unwrap().insert(mind_ref.id);
+                mind.relations
+                    .get_mut(&add)
+                    .unwrap()
+                    .insert(target_id.clone());
+                let target = city.population.get_mut(target_id).unwrap();
+                if !target.relations.contains_key(&add) {
+                    target.relations.insert(add.clone(), BTreeSet::new());
                 }
+                target.relations.get_mut(&add).unwrap().insert(*mind_id);
             }
         }
         return city;
     }
 
+    // the minds one citizen would like to get to know this year, drawn from the city as it
+    // was at the start of the year
+    fn temp_propose_friends(
+        city: &City,
+        age_cache: &BTreeMap<u32, Vec<Uuid>>,
+        m_id: &MindId,
+        rng: &mut StdRng,
+    ) -> Vec<Uuid> {
+        let mind = city.population.get(m_id).unwrap();
+        let age = mind.age;
+        let adult_age = city.culture_by_id(&mind.culture).adult_age;
+        let current_friends = filter_to_social_relations(&mind.relations);
+        let mut excluded_relations = filter_to_friend_exclusion_list(&mind.relations);
+        excluded_relations.extend(current_friends.iter().cloned());
+        excluded_relations.insert(m_id.clone());
+        let friend_count = current_friends.len();
+        let to_add = ((rng.gen::<f32>() * 20.0) as i32 - friend_count as i32).max(0) as usize;
+
+        let max_gap = if friend_count < 2 && (age > adult_age + 6) {
+            15
+        } else if friend_count < 5 && (age > adult_age + 3) {
+            10
+        } else {
+            5
+        };
+
+        let buckets = temp_eligible_friend_buckets(
+            age_cache,
+            (age as i32 - max_gap as i32).max(adult_age as i32) as u32,
+            age + max_gap,
+        );
+        return (0..to_add)
+            .filter_map(|_i| temp_draw_eligible_friend(&buckets, &excluded_relations, rng))
+            .collect();
+    }
+
     impl City {
-        pub fn temp_add_friends(self: &mut Self) {
-            let city = self;
-            let mut rng = rand::thread_rng();
-            let city_clone = city.clone();
-            let mind_ids = city_clone.current_citizens();
-            let age_cache = generate_age_cache(&city);
-            for m_id in mind_ids.clone() {
-                let mind_clone = city.population.get(&m_id).unwrap().clone();
-                let social_relations = filter_to_social_relations(&mind_clone.relations);
-                let current_friends: HashSet<&Uuid> =
-                    HashSet::from_iter(social_relations.iter().map(|(id, _rest)| id));
-                let temp_exclude = filter_to_friend_exclusion_list(&mind_clone.relations);
-                let mut excluded_relations: HashSet<&Uuid> =
-                    HashSet::from_iter(temp_exclude.iter().map(|(id, _rest)| id));
-                excluded_relations.extend(&current_friends);
-                let friend_count = current_friends.len();
-                let to_add =
-                    ((rng.gen::<f32>() * 20.0) as i32 - friend_count as i32).max(0) as usize;
-
-                let max_gap = if friend_count < 2 && (mind_clone.age > city.culture.adult_age + 6) {
-                    15
-                } else if friend_count < 5 && (mind_clone.age > city.culture.adult_age + 3) {
-                    10
-                } else {
-                    5
-                };
-
-                let source_list = temp_generate_eligible_friend_list(
-                    &age_cache,
-                    (mind_clone.age as i32 - max_gap as i32).max(city.culture.adult_age as i32)
-                        as u32,
-                    mind_clone.age + max_gap,
-                    &excluded_relations,
+        pub fn temp_add_friends(self: &mut Self, rng: &mut StdRng) {
+            let mind_ids = self.current_citizens();
+            let age_cache = generate_age_cache(&self);
+            let city: &City = self;
+            let proposals = propose_for_minds(&mind_ids, phase_seed(rng), |m_id, rng| {
+                return (
+                    m_id.clone(),
+                    temp_propose_friends(city, &age_cache, m_id, rng),
                 );
-                for _i in 0..to_add {
-                    let index = (rng.gen::<f32>() * source_list.len() as f32) as usize;
-                    let possible_target_mind_id = source_list.get(index);
-                    if possible_target_mind_id.is_some() {
-                        let target_mind_id = possible_target_mind_id.unwrap();
-                        if !mind_clone.is_relation_of(target_mind_id) {
-                            let source_mind_mut = city.population.get_mut(&m_id).unwrap();
-                            if !source_mind_mut
-                                .relations
-                                .contains_key(&RelationVerb::Acquaintance)
-                            {
-                                source_mind_mut
-                                    .relations
-                                    .insert(RelationVerb::Acquaintance, HashSet::new());
-                            }
+            });
+            let city = self;
+            for (m_id, targets) in proposals {
+                for target_mind_id in targets {
+                    let is_relation = city
+                        .population
+                        .get(&m_id)
+                        .unwrap()
+                        .is_relation_of(&target_mind_id);
+                    if !is_relation {
+                        let source_mind_mut = city.population.get_mut(&m_id).unwrap();
+                        if !source_mind_mut
+                            .relations
+                            .contains_key(&RelationVerb::Acquaintance)
+                        {
                             source_mind_mut
                                 .relations
-                                .get_mut(&RelationVerb::Acquaintance)
-                                .unwrap()
-                                .insert(target_mind_id.clone());
+                                .insert(RelationVerb::Acquaintance, BTreeSet::new());
+                        }
+                        source_mind_mut
+                            .relations
+                            .get_mut(&RelationVerb::Acquaintance)
+                            .unwrap()
+                            .insert(target_mind_id.clone());
 
-                            let target_mind_mut = city.population.get_mut(&target_mind_id).unwrap();
-                            if !target_mind_mut
-                                .relations
-                                .contains_key(&RelationVerb::Acquaintance)
-                            {
-                                target_mind_mut
-                                    .relations
-                                    .insert(RelationVerb::Acquaintance, HashSet::new());
-                            }
+                        let target_mind_mut = city.population.get_mut(&target_mind_id).unwrap();
+                        if !target_mind_mut
+                            .relations
+                            .contains_key(&RelationVerb::Acquaintance)
+                        {
                             target_mind_mut
                                 .relations
-                                .get_mut(&RelationVerb::Acquaintance)
-                                .unwrap()
-                                .insert(m_id.clone());
+                                .insert(RelationVerb::Acquaintance, BTreeSet::new());
                         }
+                        target_mind_mut
+                            .relations
+                            .get_mut(&RelationVerb::Acquaintance)
+                            .unwrap()
+                            .insert(m_id.clone());
                     }
                 }
             }
             for m in mind_ids {
-                temp_friend_evolution(city, &m);
+                temp_friend_evolution(city, &m, rng);
             }
             city.add_timestamp("temp find friends");
         }
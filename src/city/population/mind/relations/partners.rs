@@ -1,27 +1,26 @@
 pub mod partners {
-    use std::collections::HashSet;
+    use std::collections::{BTreeSet, HashSet};
 
-    use rand::{seq::SliceRandom, Rng};
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng};
     use uuid::Uuid;
 
     use crate::city::{
+        chronicle::chronicle::Event,
         city::City,
+        parallel::parallel::{phase_seed, propose_for_minds},
         population::mind::{
-            mind::{Gender, Mind, Sexuality},
+            mind::{Gender, Mind, MindId, Sexuality},
             relations::relations::RelationVerb,
         },
     };
 
     // const PARTNER_CHANCE_GENERAL: f32 = 0.33; // multiple annual chances
-    const PARTNER_MARRIAGE_RATE: f32 = 0.075; // single anunal chance
-    const PARTNER_SPLIT_RATE: f32 = 0.06; // single annual chance
-    const MARRIAGE_SPLIT_RATE: f32 = 0.03; // single annual chance
 
     impl City {
-        pub fn update_mind_partner_relations(self: &mut Self) {
-            temp_find_partners(self);
+        pub fn update_mind_partner_relations(self: &mut Self, rng: &mut StdRng) {
+            temp_find_partners(self, rng);
 
-            temp_partner_evolution(self);
+            temp_partner_evolution(self, rng);
         }
     }
 
@@ -53,137 +52,161 @@ pub mod partners {
         return output;
     }
 
-    fn is_sexuality_compatible(a: &Mind, b: &Mind) -> bool {
+    pub fn is_sexuality_compatible(a: &Mind, b: &Mind) -> bool {
         let a_target = target_sexuality_genders(a);
         let b_target = target_sexuality_genders(b);
         return a_target.contains(&b.gender) && b_target.contains(&a.gender);
     }
 
-    pub fn temp_find_partners<'a>(city: &'a mut City) -> &'a mut City {
-        let culture = city.culture.clone();
-        // let mut rng = rand::thread_rng();
-        let citizen_ids = city.current_citizens();
+    // single friends the mind could get together with, in the order they would be asked,
+    // judged from the city as it was at the start of the search
+    fn temp_propose_partners(city: &City, id: &MindId, rng: &mut StdRng) -> Vec<Uuid> {
+        let adult = |m: &Mind| m.age > city.culture_by_id(&m.culture).adult_age;
+        let population = &city.population;
+        let mind = population.get(id).unwrap();
 
-        for id in &citizen_ids {
-            let population = &mut city.population;
-            let mind = population.get(id).unwrap().clone();
+        if !(mind.is_single() && adult(mind))
+        // && rng.gen::<f32>() < PARTNER_CHANCE_GENERAL
+        {
+            return Vec::new();
+        }
+        let mut single_friend_ids: Vec<Uuid> = mind
+            .relations
+            .iter()
+            .map(|(verb, ids)| {
+                return if verb.eq(&&RelationVerb::Friend)
+                    || verb.eq(&&RelationVerb::CloseFriend)
+                    || verb.eq(&&RelationVerb::Acquaintance)
+                {
+                    ids.iter()
+                        .filter(|id| population.get(id).unwrap().is_single())
+                        .cloned()
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+            })
+            .flatten()
+            .collect();
+        single_friend_ids.shuffle(rng);
+        single_friend_ids.retain(|f_id| {
+            let f = &population.get(f_id).unwrap();
+            return f.alive && is_sexuality_compatible(mind, f) && adult(f);
+        });
+        return single_friend_ids;
+    }
 
-            if mind.is_single() && mind.age > city.culture.adult_age
-            // && rng.gen::<f32>() < PARTNER_CHANCE_GENERAL
-            {
-                let mut single_friend_ids: Vec<&Uuid> = mind
-                    .relations
-                    .iter()
-                    .map(|(verb, ids)| {
-                        return if verb.eq(&&RelationVerb::Friend)
-                            || verb.eq(&&RelationVerb::CloseFriend)
-                            || verb.eq(&&RelationVerb::Acquaintance)
-                        {
-                            ids.iter()
-                                .filter(|id| population.get(id).unwrap().is_single())
-                                .collect()
-                        } else {
-                            Vec::new()
-                        };
-                    })
-                    .flatten()
-                    .collect();
-                single_friend_ids.shuffle(&mut rand::thread_rng());
-                let possible_target = single_friend_ids.iter().find(|f_id| {
-                    let f = &population.get(f_id).unwrap();
-                    return is_sexuality_compatible(&mind, f) && f.age > culture.adult_age;
-                });
-                if possible_target.is_some() {
-                    let target_id = possible_target.unwrap();
+    pub fn temp_find_partners<'a>(city: &'a mut City, rng: &mut StdRng) -> &'a mut City {
+        let citizen_ids = city.current_citizens();
+        let reference: &City = city;
+        let proposals = propose_for_minds(&citizen_ids, phase_seed(rng), |id, rng| {
+            return (id.clone(), temp_propose_partners(reference, id, rng));
+        });
 
-                    let mind_mut = population.get_mut(&id).unwrap();
-                    if mind_mut.relations.contains_key(&RelationVerb::Friend) {
-                        mind_mut
-                            .relations
-                            .get_mut(&RelationVerb::Friend)
-                            .unwrap()
-                            .retain(|r_id| !r_id.eq(&target_id));
-                    }
-                    if mind_mut.relations.contains_key(&RelationVerb::CloseFriend) {
-                        mind_mut
-                            .relations
-                            .get_mut(&RelationVerb::CloseFriend)
-                            .unwrap()
-                            .retain(|r_id| !r_id.eq(&target_id));
-                    }
+        for (id, candidates) in proposals {
+            // earlier minds in the year may already have paired off with either side
+            let population = &city.population;
+            let possible_target = if population.get(&id).unwrap().is_single() {
+                candidates
+                    .into_iter()
+                    .find(|f_id| population.get(f_id).unwrap().is_single())
+            } else {
+                None
+            };
+            if possible_target.is_some() {
+                let target_id = possible_target.unwrap();
+                let population = &mut city.population;
 
-                    if !mind_mut.relations.contains_key(&RelationVerb::Partner) {
-                        mind_mut
-                            .relations
-                            .insert(RelationVerb::Partner, HashSet::new());
-                    }
+                let mind_mut = population.get_mut(&id).unwrap();
+                if mind_mut.relations.contains_key(&RelationVerb::Friend) {
+                    mind_mut
+                        .relations
+                        .get_mut(&RelationVerb::Friend)
+                        .unwrap()
+                        .retain(|r_id| !r_id.eq(&target_id));
+                }
+                if mind_mut.relations.contains_key(&RelationVerb::CloseFriend) {
                     mind_mut
                         .relations
-                        .get_mut(&RelationVerb::Partner)
+                        .get_mut(&RelationVerb::CloseFriend)
                         .unwrap()
-                        .insert((*target_id).clone());
+                        .retain(|r_id| !r_id.eq(&target_id));
+                }
 
-                    let target_mut = population.get_mut(&target_id).unwrap();
-                    if target_mut.relations.contains_key(&RelationVerb::Friend) {
-                        target_mut
-                            .relations
-                            .get_mut(&RelationVerb::Friend)
-                            .unwrap()
-                            .retain(|r_id| !r_id.eq(&id));
-                    }
-                    if target_mut
+                if !mind_mut.relations.contains_key(&RelationVerb::Partner) {
+                    mind_mut
                         .relations
-                        .contains_key(&RelationVerb::CloseFriend)
-                    {
-                        target_mut
-                            .relations
-                            .get_mut(&RelationVerb::CloseFriend)
-                            .unwrap()
-                            .retain(|r_id| !r_id.eq(&id));
-                    }
+                        .insert(RelationVerb::Partner, BTreeSet::new());
+                }
+                mind_mut
+                    .relations
+                    .get_mut(&RelationVerb::Partner)
+                    .unwrap()
+                    .insert(target_id.clone());
 
-                    if !target_mut.relations.contains_key(&RelationVerb::Partner) {
-                        target_mut
-                            .relations
-                            .insert(RelationVerb::Partner, HashSet::new());
-                    }
+                let target_mut = population.get_mut(&target_id).unwrap();
+                if target_mut.relations.contains_key(&RelationVerb::Friend) {
+                    target_mut
+                        .relations
+                        .get_mut(&RelationVerb::Friend)
+                        .unwrap()
+                        .retain(|r_id| !r_id.eq(&id));
+                }
+                if target_mut
+                    .relations
+                    .contains_key(&RelationVerb::CloseFriend)
+                {
                     target_mut
                         .relations
-                        .get_mut(&RelationVerb::Partner)
+                        .get_mut(&RelationVerb::CloseFriend)
                         .unwrap()
-                        .insert(id.clone());
+                        .retain(|r_id| !r_id.eq(&id));
+                }
+
+                if !target_mut.relations.contains_key(&RelationVerb::Partner) {
+                    target_mut
+                        .relations
+                        .insert(RelationVerb::Partner, BTreeSet::new());
                 }
+                target_mut
+                    .relations
+                    .get_mut(&RelationVerb::Partner)
+                    .unwrap()
+                    .insert(id.clone());
+                city.chronicle.record(Event::Partnered {
+                    year: city.year,
+                    minds: [id.clone(), target_id.clone()],
+                });
             }
         }
         city.add_timestamp("find partners");
         return city;
     }
 
-    fn temp_partner_evolution<'a>(city: &'a mut City) -> &'a mut City {
-        // TODO - improve perf. Major perf bottleneck
-        let mut rng = rand::thread_rng();
+    fn temp_partner_evolution<'a>(city: &'a mut City, rng: &mut StdRng) -> &'a mut City {
         let citizen_ids = city.current_citizens();
-        let mut reference_citizens = city.population.clone();
+        let config = city.config.partners.clone();
 
-        let mut processed: HashSet<Uuid> = HashSet::new();
+        let mut processed: BTreeSet<Uuid> = BTreeSet::new();
         city.add_timestamp("partner evolution overhead");
         for id in citizen_ids {
-            let mind = reference_citizens.get(&id).unwrap();
+            let mind = city.population.get(&id).unwrap();
             if !processed.contains(&id) && !mind.is_single() {
                 processed.insert(id.clone());
 
-                let possible_partner: Option<(&Uuid, RelationVerb)> =
-                    mind.get_current_romantic_partner();
+                let possible_partner: Option<(Uuid, RelationVerb)> = mind
+                    .get_current_romantic_partner()
+                    .map(|(partner_id, verb)| (partner_id.clone(), verb));
 
                 if possible_partner.is_some() {
                     let (partner_id, verb) = possible_partner.unwrap();
-                    let partner = reference_citizens.get(partner_id).unwrap();
+                    let partner = city.population.get(&partner_id).unwrap();
                     if partner.alive {
-                        processed.insert((*partner_id).clone());
+                        processed.insert(partner_id.clone());
                         let split_chance = if verb.eq(&RelationVerb::Spouse) {
-                            MARRIAGE_SPLIT_RATE
+                            config.marriage_split_rate
                         } else {
-                            PARTNER_SPLIT_RATE
+                            config.partner_split_rate
                         };
                         let mut maybe_new_verb: Option<RelationVerb> =
                             if verb.eq(&RelationVerb::Partner) {
@@ -195,19 +218,26 @@ pub mod partners {
                             maybe_new_verb = Some(verb.clone());
                         }
                         if verb.eq(&RelationVerb::Partner)
-                            && rng.gen::<f32>() < PARTNER_MARRIAGE_RATE
+                            && rng.gen::<f32>() < config.marriage_rate
                         {
                             maybe_new_verb = Some(RelationVerb::Spouse);
                         }
 
                         if maybe_new_verb.is_some() {
                             let new_verb = maybe_new_verb.unwrap();
-                            let partner = reference_citizens.get(partner_id).unwrap();
 
                             let new_names = if new_verb.eq(&RelationVerb::Spouse) {
+                                // couples from different cultures may marry in either way
                                 let mut surname_formats =
-                                    city.culture.marriage_surname_formats.clone();
-                                surname_formats.shuffle(&mut rng);
+                                    city.culture_of(&id).marriage_surname_formats.clone();
+                                if !mind.culture.eq(&partner.culture) {
+                                    surname_formats.extend(
+                                        city.culture_of(&partner_id)
+                                            .marriage_surname_formats
+                                            .clone(),
+                                    );
+                                }
+                                surname_formats.shuffle(rng);
                                 let surname_format = surname_formats.first().unwrap();
                                 [
                                     Some(surname_format.0.render(
@@ -215,12 +245,14 @@ pub mod partners {
                                         mind.last_name.clone(),
                                         partner.first_name.clone(),
                                         partner.last_name.clone(),
+                                        rng,
                                     )),
                                     Some(surname_format.1.render(
                                         mind.first_name.clone(),
                                         mind.last_name.clone(),
                                         partner.first_name.clone(),
                                         partner.last_name.clone(),
+                                        rng,
                                     )),
                                 ]
                             } else {
@@ -228,14 +260,14 @@ pub mod partners {
                             };
 
                             for (i, [active_id, part_id]) in
-                                vec![[&id, partner_id], [partner_id, &id]]
+                                vec![[&id, &partner_id], [&partner_id, &id]]
                                     .iter()
                                     .enumerate()
                             {
                                 let mind_mut = city.population.get_mut(&active_id).unwrap();
 
                                 if !mind_mut.relations.contains_key(&new_verb) {
-                                    mind_mut.relations.insert(new_verb.clone(), HashSet::new());
+                                    mind_mut.relations.insert(new_verb.clone(), BTreeSet::new());
                                 }
 
                                 mind_mut
@@ -255,7 +287,15 @@ pub mod partners {
                                     mind_mut.last_name = mind_mut.origional_last_name.clone();
                                 }
                             }
-                            reference_citizens = city.population.clone();
+                            let minds = [id.clone(), partner_id.clone()];
+                            let year = city.year;
+                            if new_verb.eq(&RelationVerb::Spouse) && verb.ne(&new_verb) {
+                                city.record_event(Event::Married { year, minds });
+                            } else if new_verb.eq(&RelationVerb::ExPartner) {
+                                city.record_event(Event::Separated { year, minds });
+                            } else if new_verb.eq(&RelationVerb::ExSpouse) {
+                                city.record_event(Event::Divorced { year, minds });
+                            }
                         }
                     }
                 }
@@ -272,14 +312,33 @@ pub mod partners {
     fn test_matching() {
         use crate::city::{
             city::Era,
+            config::config::SimulationConfig,
             culture::culture::random_culture,
             population::mind::mind::{random_mind, Gender, Sexuality},
+            site::site::CitySite,
+        };
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
         };
         use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
 
         let dict = build_dictionary_from_folder("./data_files/");
-        let culture = random_culture(&dict, Era::Medieval);
-        let mut mind = random_mind(&dict, &culture, 0);
+        let templates = build_templates_from_folder("./data_files/");
+        let mut rng = seeded_rng(0, "test", 0);
+        let culture = random_culture(
+            &dict,
+            &templates,
+            &CitySite::default(),
+            Era::Medieval,
+            &mut rng,
+        );
+        let mut mind = random_mind(
+            &dict,
+            &culture,
+            &SimulationConfig::default().demographics,
+            0,
+            &mut rng,
+        );
         mind.gender = Gender::Male;
         mind.sexuality = Sexuality::Hetrosexual;
         println!(
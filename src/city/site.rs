@@ -0,0 +1,349 @@
+pub mod site {
+    use std::collections::{BTreeSet, HashSet};
+    use std::fmt;
+
+    use procgen_templater::dictionary::{dictionary::Dictionary, word::word::WordType};
+    use rand::{rngs::StdRng, Rng};
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use crate::{
+        city::culture::culture::Culture,
+        grammar::grammar::render_list,
+        seeded::{seeded::random_word, templates::templates::Templates},
+    };
+
+    // city name templates are redrawn this many times before settling for a district's name
+    const CITY_NAME_ATTEMPTS: usize = 10;
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub enum Biome {
+        Forest,
+        Marsh,
+        Fen,
+        Plain,
+        Desert,
+        Hill,
+    }
+
+    impl fmt::Display for Biome {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Biome::Forest => write!(f, "Forest"),
+                Biome::Marsh => write!(f, "Marsh"),
+                Biome::Fen => write!(f, "Fen"),
+                Biome::Plain => write!(f, "Plain"),
+                Biome::Desert => write!(f, "Desert"),
+                Biome::Hill => write!(f, "Hill"),
+            }
+        }
+    }
+
+    impl Biome {
+        fn from_base(base: &str) -> Biome {
+            return match base {
+                "Forest" => Biome::Forest,
+                "Marsh" => Biome::Marsh,
+                "Fen" => Biome::Fen,
+                "Desert" => Biome::Desert,
+                "Hill" => Biome::Hill,
+                _ => Biome::Plain,
+            };
+        }
+
+        // odds of a (coast, river, lake, hills) at a city founded in this biome
+        fn feature_chances(self: &Self) -> (f32, f32, f32, f32) {
+            return match self {
+                Biome::Forest => (0.4, 0.6, 0.25, 0.3),
+                Biome::Marsh => (0.6, 0.8, 0.4, 0.05),
+                Biome::Fen => (0.5, 0.7, 0.4, 0.05),
+                Biome::Plain => (0.5, 0.6, 0.2, 0.15),
+                Biome::Desert => (0.3, 0.3, 0.1, 0.35),
+                Biome::Hill => (0.4, 0.5, 0.25, 1.0),
+            };
+        }
+
+        // the (kind, size) tag groups of the creatures hunted or herded on this land
+        pub fn game_tags(self: &Self) -> Vec<Vec<String>> {
+            let (kinds, sizes): (Vec<&str>, Vec<&str>) = match self {
+                Biome::Forest => (vec!["Mammal", "Bird"], vec!["Medium", "Large"]),
+                Biome::Plain => (vec!["Mammal", "Bird"], vec!["Normal", "Medium", "Large"]),
+                Biome::Marsh | Biome::Fen => (vec!["Bird", "Mammal"], vec!["Normal", "Large"]),
+                Biome::Desert | Biome::Hill => (vec!["Mammal"], vec!["Large"]),
+            };
+            return vec![
+                kinds.iter().map(|t| t.to_string()).collect(),
+                sizes.iter().map(|t| t.to_string()).collect(),
+            ];
+        }
+
+        // the kinds of crop that grow well on this land
+        pub fn crop_tags(self: &Self) -> Vec<String> {
+            let tags: Vec<&str> = match self {
+                Biome::Forest => vec!["Fruit", "Vegetable", "Leaf"],
+                Biome::Marsh | Biome::Fen => vec!["Vegetable", "Leaf"],
+                Biome::Plain => vec!["Grain", "Vegetable"],
+                Biome::Desert => vec!["Fruit"],
+                Biome::Hill => vec!["Vegetable", "Leaf", "Fruit"],
+            };
+            return tags.iter().map(|t| t.to_string()).collect();
+        }
+    }
+
+    // Where the city was founded. Drawn before anything else so the land decides what the
+    // culture eats and which AreaFeature words its places can be named after.
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct CitySite {
+        pub biome: Biome,
+        pub coastal: bool,
+        pub river: bool,
+        pub lake: bool,
+        pub hills: bool,
+        pub features: BTreeSet<String>,
+    }
+
+    impl Default for CitySite {
+        fn default() -> Self {
+            return CitySite {
+                biome: Biome::Plain,
+                coastal: false,
+                river: false,
+                lake: false,
+                hills: false,
+                features: BTreeSet::new(),
+            };
+        }
+    }
+
+    impl CitySite {
+        // features with no rule here, like Town, can be found anywhere
+        pub fn has_room_for_feature(self: &Self, base: &str) -> bool {
+            return match base {
+                "Bay" | "Beach" | "Harbour" | "Port" | "Dock" | "Straight" => self.coastal,
+                "Mouth" => self.coastal && self.river,
+                "River" | "Ford" | "Bridge" => self.river,
+                "Fall" => self.river && self.hills,
+                "Lake" | "Loch" | "Pool" => self.lake,
+                "Portage" => self.lake && self.river,
+                "Mountain" | "Ridge" | "Valley" => self.hills,
+                "Wood" => self.biome.eq(&Biome::Forest),
+                "Field" => self.biome.eq(&Biome::Plain) || self.biome.eq(&Biome::Hill),
+                _ => true,
+            };
+        }
+
+        // template tags naming features this site doesn't have
+        pub fn missing_template_tags(self: &Self) -> Vec<String> {
+            let mut output = Vec::new();
+            if !self.coastal {
+                output.push(String::from("Coastal"));
+            }
+            return output;
+        }
+
+        // a copy of the dictionary where AreaFeature only covers the features at this site
+        pub fn dictionary(self: &Self, dict: &Dictionary) -> Dictionary {
+            let mut output = dict.clone();
+            let key = (WordType::Noun, String::from("AreaFeature"));
+            if output.index.tag_words.contains_key(&key) {
+                let words = &dict.words;
+                output.index.tag_words.get_mut(&key).unwrap().retain(|id| {
+                    words
+                        .get(id)
+                        .is_some_and(|w| self.features.contains(&w.base))
+                });
+            }
+            return output;
+        }
+
+        // an AreaName rendered from the features at this site, given the site's dictionary
+        pub fn random_place_name(
+            self: &Self,
+            site_dict: &Dictionary,
+            templates: &Templates,
+            rng: &mut StdRng,
+        ) -> String {
+            let name_template = templates
+                .get_random_template_without(
+                    vec![vec!["AreaName".to_string()]],
+                    self.missing_template_tags(),
+                    rng,
+                )
+                .unwrap();
+            return name_template.render_as_title(site_dict, rng).unwrap();
+        }
+
+        pub fn describe(self: &Self) -> String {
+            let mut surroundings: Vec<&str> = Vec::new();
+            if self.river {
+                surroundings.push("a river");
+            }
+            if self.lake {
+                surroundings.push("a lake");
+            }
+            if self.hills && !self.biome.eq(&Biome::Hill) {
+                surroundings.push("hills");
+            }
+            let mut output = format!(
+                "{} {}",
+                if self.coastal { "Coastal" } else { "Inland" },
+                if self.biome.eq(&Biome::Hill) {
+                    String::from("hill country")
+                } else {
+                    self.biome.to_string().to_ascii_lowercase()
+                }
+            );
+            if surroundings.len() > 0 {
+                output += &format!(" with {}", render_list(surroundings));
+            }
+            return output;
+        }
+    }
+
+    // the founders name their city after the site's features, last names of their own era
+    // and the titles and families of their historical figures, drawn from a dictionary
+    // rebalanced for their culture
+    pub fn random_city_name(
+        names: &Dictionary,
+        templates: &Templates,
+        site: &CitySite,
+        culture: &Culture,
+        rng: &mut StdRng,
+    ) -> String {
+        let mut naming_dict = site.dictionary(names);
+        let era_words: HashSet<Uuid> = culture
+            .era
+            .tags()
+            .into_iter()
+            .filter_map(|tag| naming_dict.index.tag_words.get(&(WordType::Noun, tag)))
+            .flatten()
+            .cloned()
+            .collect();
+        let last_name_key = (WordType::Noun, String::from("LastName"));
+        if era_words.len() > 0 && naming_dict.index.tag_words.contains_key(&last_name_key) {
+            naming_dict
+                .index
+                .tag_words
+                .get_mut(&last_name_key)
+                .unwrap()
+                .retain(|id| era_words.contains(id));
+        }
+        for _attempt in 0..CITY_NAME_ATTEMPTS {
+            let template = templates.get_random_template_without(
+                vec![vec!["CityName".to_string()], vec![culture.era.to_string()]],
+                site.missing_template_tags(),
+                rng,
+            );
+            if template.is_none() {
+                break;
+            }
+            let name = template.unwrap().render(&naming_dict, rng);
+            if name.is_some() {
+                return name.unwrap();
+            }
+        }
+        return site.random_place_name(&site.dictionary(names), templates, rng);
+    }
+
+    pub fn random_site(dict: &Dictionary, rng: &mut StdRng) -> CitySite {
+        let biome_word = random_word(dict, (WordType::Noun, vec![vec!["Biome".to_string()]]), rng);
+        let biome = if biome_word.is_some() {
+            Biome::from_base(&biome_word.unwrap().base)
+        } else {
+            Biome::Plain
+        };
+        let (coast_chance, river_chance, lake_chance, hills_chance) = biome.feature_chances();
+        let mut site = CitySite {
+            coastal: rng.gen::<f32>() < coast_chance,
+            river: rng.gen::<f32>() < river_chance,
+            lake: rng.gen::<f32>() < lake_chance,
+            hills: rng.gen::<f32>() < hills_chance,
+            biome,
+            features: BTreeSet::new(),
+        };
+        let feature_ids = dict
+            .index
+            .tag_words
+            .get(&(WordType::Noun, String::from("AreaFeature")));
+        if feature_ids.is_some() {
+            site.features = feature_ids
+                .unwrap()
+                .iter()
+                .filter_map(|id| dict.words.get(id))
+                .map(|w| w.base.clone())
+                .filter(|base| site.has_room_for_feature(base))
+                .collect();
+        }
+        return site;
+    }
+
+    #[test]
+    fn test_site_features_fit_the_land() {
+        use crate::seeded::seeded::seeded_rng;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let mut seen_coast = false;
+        let mut seen_inland = false;
+        for seed in 0..40 {
+            let site = random_site(&dict, &mut seeded_rng(seed, "site", 0));
+            assert!(site.features.contains("Town"));
+            assert_eq!(site.features.contains("Harbour"), site.coastal);
+            assert_eq!(site.features.contains("River"), site.river);
+            if site.biome.eq(&Biome::Hill) {
+                assert!(site.hills);
+            }
+            seen_coast = seen_coast || site.coastal;
+            seen_inland = seen_inland || !site.coastal;
+
+            let site_dict = site.dictionary(&dict);
+            let mut rng = seeded_rng(seed, "feature", 0);
+            for _i in 0..20 {
+                let feature = random_word(
+                    &site_dict,
+                    (WordType::Noun, vec![vec!["AreaFeature".to_string()]]),
+                    &mut rng,
+                );
+                assert!(site.features.contains(&feature.unwrap().base));
+            }
+        }
+        assert!(seen_coast && seen_inland);
+    }
+
+    #[test]
+    fn test_city_names_fit_site_and_era() {
+        use crate::city::{
+            city::Era,
+            culture::culture::{random_culture, rebalance_dict_for_culture},
+        };
+        use crate::seeded::{
+            seeded::seeded_rng, templates::templates::build_templates_from_folder,
+        };
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        for seed in 0..12 {
+            for era in [Era::Modern, Era::Medieval] {
+                let site = random_site(&dict, &mut seeded_rng(seed, "site", 0));
+                let culture = random_culture(
+                    &dict,
+                    &templates,
+                    &site,
+                    era,
+                    &mut seeded_rng(seed, "culture", 0),
+                );
+                let name = random_city_name(
+                    &rebalance_dict_for_culture(&culture, &dict),
+                    &templates,
+                    &site,
+                    &culture,
+                    &mut seeded_rng(seed, "name", 0),
+                );
+                assert!(name.len() > 0);
+                assert!(!name.contains("NOUN") && !name.contains("ADJECTIVE"));
+                if !site.coastal {
+                    assert!(!name.contains("-On-Sea") && !name.starts_with("Port "));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+pub mod snapshot {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        fs,
+        path::Path,
+    };
+
+    use procgen_templater::dictionary::{dictionary::Dictionary, word::word::Word};
+    use serde::{Deserialize, Serialize};
+
+    use crate::city::{
+        city::City,
+        culture::culture::{Culture, CultureId},
+        dieties::dieties::{Diety, DietyId},
+    };
+    use crate::seeded::templates::templates::Templates;
+
+    // Bump whenever a saved field is added, removed or changes meaning. Older snapshots are
+    // refused rather than filled in with defaults, which would leave them in states the
+    // simulation never reaches, like employed minds without a career.
+    // 2: households, site, minorities, epidemics, government, houses and careers
+    pub const SNAPSHOT_VERSION: u32 = 2;
+
+    // Words belong to the dictionary rather than the city, so only enough to find them
+    // again is saved and they are looked back up when a snapshot is loaded
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct SavedWord {
+        pub base: String,
+        pub tags: BTreeSet<String>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct SavedDiety {
+        pub id: DietyId,
+        pub realms: Vec<SavedWord>,
+        pub name: String,
+        pub form: SavedWord,
+        pub form2: String,
+    }
+
+    // the parts of a culture made of words
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct SavedCulture {
+        pub staple_meats: Vec<SavedWord>,
+        pub staple_plants: Vec<SavedWord>,
+        pub historical_names: Vec<(SavedWord, SavedWord, SavedWord)>,
+        pub dieties: Vec<SavedDiety>,
+    }
+
+    // the founding culture's words sit at the top level, each minority's under its id
+    #[derive(Serialize, Deserialize)]
+    struct CitySnapshot {
+        version: u32,
+        #[serde(flatten)]
+        culture: SavedCulture,
+        minorities: BTreeMap<CultureId, SavedCulture>,
+        city: City,
+    }
+
+    fn save_word(word: &Word) -> SavedWord {
+        return SavedWord {
+            base: word.base.clone(),
+            tags: word.tags.iter().cloned().collect(),
+        };
+    }
+
+    fn load_word(dict: &Dictionary, saved: &SavedWord) -> Result<Word, String> {
+        let mut matches: Vec<&Word> = dict
+            .words
+            .values()
+            .filter(|w| w.base.eq(&saved.base) && saved.tags.iter().all(|t| w.tags.contains(t)))
+            .collect();
+        matches.sort_by_key(|w| w.tags.len());
+        let word = matches.first();
+        if word.is_none() {
+            return Err(format!(
+                "Snapshot word \"{}\" is missing from the dictionary",
+                saved.base
+            ));
+        }
+        return Ok((*word.unwrap()).clone());
+    }
+
+    fn save_diety(diety: &Diety) -> SavedDiety {
+        return SavedDiety {
+            id: diety.id.clone(),
+            realms: diety.realms.iter().map(save_word).collect(),
+            name: diety.name.clone(),
+            form: save_word(&diety.form),
+            form2: diety.form2.clone(),
+        };
+    }
+
+    fn load_diety(dict: &Dictionary, saved: &SavedDiety) -> Result<Diety, String> {
+        let mut realms: Vec<Word> = Vec::new();
+        for realm in &saved.realms {
+            realms.push(load_word(dict, realm)?);
+        }
+        return Ok(Diety {
+            id: saved.id.clone(),
+            realms,
+            name: saved.name.clone(),
+            form: load_word(dict, &saved.form)?,
+            form2: saved.form2.clone(),
+        });
+    }
+
+    fn save_culture(culture: &Culture) -> SavedCulture {
+        return SavedCulture {
+            staple_meats: culture.staple_meats.iter().map(save_word).collect(),
+            staple_plants: culture.staple_plants.iter().map(save_word).collect(),
+            historical_names: culture
+                .historical_names
+                .iter()
+                .map(|(a, b, c)| (save_word(a), save_word(b), save_word(c)))
+                .collect(),
+            dieties: culture.dieties.values().map(save_diety).collect(),
+        };
+    }
+
+    fn load_culture(
+        dict: &Dictionary,
+        saved: &SavedCulture,
+        culture: &mut Culture,
+    ) -> Result<(), String> {
+        for word in &saved.staple_meats {
+            culture.staple_meats.push(load_word(dict, word)?);
+        }
+        for word in &saved.staple_plants {
+            culture.staple_plants.push(load_word(dict, word)?);
+        }
+        for (a, b, c) in &saved.historical_names {
+            culture.historical_names.push((
+                load_word(dict, a)?,
+                load_word(dict, b)?,
+                load_word(dict, c)?,
+            ));
+        }
+        for diety in &saved.dieties {
+            culture
+                .dieties
+                .insert(diety.id.clone(), load_diety(dict, diety)?);
+        }
+        return Ok(());
+    }
+
+    impl City {
+        pub fn save(self: &Self, path: &str) -> Result<(), String> {
+            let snapshot = CitySnapshot {
+                version: SNAPSHOT_VERSION,
+                culture: save_culture(&self.culture),
+                minorities: self
+                    .minorities
+                    .iter()
+                    .map(|(id, culture)| (id.clone(), save_culture(culture)))
+                    .collect(),
+                city: self.clone(),
+            };
+            let contents = serde_json::to_string(&snapshot)
+                .map_err(|e| format!("Could not serialise city: {}", e))?;
+            fs::write(Path::new(path), contents)
+                .map_err(|e| format!("Could not write snapshot to {}: {}", path, e))?;
+            return Ok(());
+        }
+
+        pub fn load(path: &str, dict: &Dictionary, templates: &Templates) -> Result<City, String> {
+            let contents = fs::read_to_string(Path::new(path))
+                .map_err(|e| format!("Could not read snapshot {}: {}", path, e))?;
+            let raw: serde_json::Value = serde_json::from_str(&contents)
+                .map_err(|e| format!("Snapshot {} is not valid JSON: {}", path, e))?;
+            let version = raw.get("version").and_then(|v| v.as_u64());
+            if version.is_none() {
+                return Err(format!("Snapshot {} has no version", path));
+            }
+            if version.unwrap() < SNAPSHOT_VERSION as u64 {
+                return Err(format!(
+                    "Snapshot {} was saved with version {}, which can't be loaded by this \
+                     version {} build. Generate the city again to carry on with it",
+                    path,
+                    version.unwrap(),
+                    SNAPSHOT_VERSION
+                ));
+            }
+            if version.unwrap() > SNAPSHOT_VERSION as u64 {
+                return Err(format!(
+                    "Snapshot {} was saved with version {}, newer than this build's {}",
+                    path,
+                    version.unwrap(),
+                    SNAPSHOT_VERSION
+                ));
+            }
+            let snapshot: CitySnapshot = serde_json::from_value(raw)
+                .map_err(|e| format!("Snapshot {} is malformed: {}", path, e))?;
+
+            let mut city = snapshot.city;
+            city.templates = templates.clone();
+            load_culture(dict, &snapshot.culture, &mut city.culture)?;
+            for (id, saved) in &snapshot.minorities {
+                let culture = city.minorities.get_mut(id);
+                if culture.is_none() {
+                    return Err(format!(
+                        "Snapshot {} has words for an unknown culture",
+                        path
+                    ));
+                }
+                load_culture(dict, saved, culture.unwrap())?;
+            }
+            city.refresh_dictionaries(dict);
+            return Ok(city);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        use crate::city::city::{random_city, Era};
+        use crate::city::config::config::SimulationConfig;
+        use crate::seeded::templates::templates::build_templates_from_folder;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut city = random_city(
+            &dict,
+            &templates,
+            &SimulationConfig::default(),
+            Era::Medieval,
+            30,
+            11,
+        );
+        for _i in 0..5 {
+            city.simulate_year(&dict);
+        }
+        let path = std::env::temp_dir().join("city_generator_snapshot_test.json");
+        city.save(path.to_str().unwrap()).unwrap();
+        let mut loaded = City::load(path.to_str().unwrap(), &dict, &templates).unwrap();
+        assert_eq!(loaded.culture, city.culture);
+        assert_eq!(loaded.population, city.population);
+        assert_eq!(loaded.institutions, city.institutions);
+
+        // snapshots from before the current format are refused rather than half loaded
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let old_path = std::env::temp_dir().join("city_generator_snapshot_test_old.json");
+        std::fs::write(
+            &old_path,
+            contents.replacen(
+                &format!("\"version\":{}", SNAPSHOT_VERSION),
+                "\"version\":1",
+                1,
+            ),
+        )
+        .unwrap();
+        let error = City::load(old_path.to_str().unwrap(), &dict, &templates).unwrap_err();
+        assert!(
+            error.contains("saved with version 1") && error.contains("Generate the city again")
+        );
+
+        // templates are rebuilt from the data folder rather than saved with every city
+        assert!(!contents.contains("\"templates\""));
+        assert_eq!(loaded.templates, city.templates);
+
+        // a loaded city should carry on exactly as the original would have
+        for _i in 0..5 {
+            city.simulate_year(&dict);
+            loaded.simulate_year(&dict);
+        }
+        assert_eq!(loaded.export_minds(), city.export_minds());
+        assert_eq!(loaded.export_institutions(), city.export_institutions());
+    }
+}
@@ -0,0 +1,501 @@
+pub mod systems {
+    use std::{collections::BTreeSet, fmt, sync::Arc};
+
+    use procgen_templater::dictionary::dictionary::Dictionary;
+    use rand::rngs::StdRng;
+    use serde::{Deserialize, Serialize};
+
+    use crate::city::city::City;
+
+    // One step of a simulated year. Each system is handed an rng seeded from the city seed,
+    // the year and its own name, so systems can be added or removed without changing the
+    // draws the others see.
+    pub trait SimulationSystem: Send + Sync {
+        fn name(self: &Self) -> &str;
+        fn run(self: &Self, city: &mut City, dict: &Dictionary, rng: &mut StdRng);
+        // names of systems that have to run before this one when they are registered
+        fn run_after(self: &Self) -> Vec<String> {
+            return Vec::new();
+        }
+        // names of systems that have to run after this one when they are registered
+        fn run_before(self: &Self) -> Vec<String> {
+            return Vec::new();
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct RegisteredSystem {
+        pub system: Arc<dyn SimulationSystem>,
+        pub enabled: bool,
+    }
+
+    // Only system names and their enabled flags are saved with a city. On load the built in
+    // systems are restored from those, along with any built in systems the save predates,
+    // downstream systems need registering again.
+    #[derive(Serialize, Deserialize, Clone)]
+    pub struct SavedSystem {
+        pub name: String,
+        pub enabled: bool,
+    }
+
+    #[derive(Clone, Serialize, Deserialize)]
+    #[serde(into = "Vec<SavedSystem>", from = "Vec<SavedSystem>")]
+    pub struct SystemRegistry {
+        pub systems: Vec<RegisteredSystem>,
+    }
+
+    impl SystemRegistry {
+        pub fn new() -> SystemRegistry {
+            return SystemRegistry {
+                systems: Vec::new(),
+            };
+        }
+
+        // registering a system with a name that is already in use replaces it in place
+        pub fn register(self: &mut Self, system: Arc<dyn SimulationSystem>) {
+            let existing = self
+                .systems
+                .iter_mut()
+                .find(|s| s.system.name().eq(system.name()));
+            if existing.is_some() {
+                existing.unwrap().system = system;
+            } else {
+                self.systems.push(RegisteredSystem {
+                    system,
+                    enabled: true,
+                });
+            }
+        }
+
+        pub fn remove(self: &mut Self, name: &str) {
+            self.systems.retain(|s| !s.system.name().eq(name));
+        }
+
+        pub fn set_enabled(self: &mut Self, name: &str, enabled: bool) {
+            for registered in self.systems.iter_mut() {
+                if registered.system.name().eq(name) {
+                    registered.enabled = enabled;
+                }
+            }
+        }
+
+        // moves a system to the given position in registration order, which decides the
+        // order of systems that have no hints between them
+        pub fn move_to(self: &mut Self, name: &str, index: usize) {
+            let position = self.systems.iter().position(|s| s.system.name().eq(name));
+            if position.is_some() {
+                let registered = self.systems.remove(position.unwrap());
+                let index = index.min(self.systems.len());
+                self.systems.insert(index, registered);
+            }
+        }
+
+        pub fn names(self: &Self) -> Vec<String> {
+            return self
+                .systems
+                .iter()
+                .map(|s| s.system.name().to_string())
+                .collect();
+        }
+
+        // enabled systems in registration order, moved as little as needed to satisfy the
+        // run_after/run_before hints. Systems caught in a hint cycle keep registration order.
+        pub fn ordered(self: &Self) -> Vec<Arc<dyn SimulationSystem>> {
+            let enabled: Vec<&Arc<dyn SimulationSystem>> = self
+                .systems
+                .iter()
+                .filter(|s| s.enabled)
+                .map(|s| &s.system)
+                .collect();
+            let names: Vec<String> = enabled.iter().map(|s| s.name().to_string()).collect();
+            let mut dependencies: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); enabled.len()];
+            for (i, system) in enabled.iter().enumerate() {
+                for after in system.run_after() {
+                    let position = names.iter().position(|n| n.eq(&after));
+                    if position.is_some() && position.unwrap() != i {
+                        dependencies[i].insert(position.unwrap());
+                    }
+                }
+                for before in system.run_before() {
+                    let position = names.iter().position(|n| n.eq(&before));
+                    if position.is_some() && position.unwrap() != i {
+                        dependencies[position.unwrap()].insert(i);
+                    }
+                }
+            }
+
+            let mut output: Vec<Arc<dyn SimulationSystem>> = Vec::new();
+            let mut placed: BTreeSet<usize> = BTreeSet::new();
+            while placed.len() < enabled.len() {
+                let next = (0..enabled.len()).find(|i| {
+                    !placed.contains(i) && dependencies[*i].iter().all(|d| placed.contains(d))
+                });
+                let next = next
+                    .unwrap_or_else(|| (0..enabled.len()).find(|i| !placed.contains(i)).unwrap());
+                placed.insert(next);
+                output.push(enabled[next].clone());
+            }
+            return output;
+        }
+    }
+
+    impl Default for SystemRegistry {
+        fn default() -> Self {
+            return default_systems();
+        }
+    }
+
+    impl fmt::Debug for SystemRegistry {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let entries: Vec<(String, bool)> = self
+                .systems
+                .iter()
+                .map(|s| (s.system.name().to_string(), s.enabled))
+                .collect();
+            return f.debug_list().entries(entries).finish();
+        }
+    }
+
+    impl PartialEq for SystemRegistry {
+        fn eq(&self, other: &Self) -> bool {
+            return self.systems.len() == other.systems.len()
+                && self
+                    .systems
+                    .iter()
+                    .zip(other.systems.iter())
+                    .all(|(a, b)| a.system.name().eq(b.system.name()) && a.enabled == b.enabled);
+        }
+    }
+
+    impl From<SystemRegistry> for Vec<SavedSystem> {
+        fn from(registry: SystemRegistry) -> Self {
+            return registry
+                .systems
+                .iter()
+                .map(|s| SavedSystem {
+                    name: s.system.name().to_string(),
+                    enabled: s.enabled,
+                })
+                .collect();
+        }
+    }
+
+    impl From<Vec<SavedSystem>> for SystemRegistry {
+        fn from(saved: Vec<SavedSystem>) -> Self {
+            let defaults = default_systems();
+            let mut registry = SystemRegistry::new();
+            for entry in &saved {
+                let builtin = defaults
+                    .systems
+                    .iter()
+                    .find(|s| s.system.name().eq(&entry.name));
+                if builtin.is_some() {
+                    registry.register(builtin.unwrap().system.clone());
+                    registry.set_enabled(&entry.name, entry.enabled);
+                }
+            }
+            // built in systems added since the city was saved run from their default place,
+            // after the built in system registered before them
+            let mut previous: Option<String> = None;
+            for default in &defaults.systems {
+                let name = default.system.name().to_string();
+                if !saved.iter().any(|s| s.name.eq(&name)) {
+                    let index = if previous.is_some() {
+                        registry
+                            .names()
+                            .iter()
+                            .position(|n| n.eq(previous.as_ref().unwrap()))
+                            .map(|p| p + 1)
+                            .unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    registry.register(default.system.clone());
+                    registry.move_to(&name, index);
+                }
+                previous = Some(name);
+            }
+            return registry;
+        }
+    }
+
+    pub struct HealthSystem;
+    pub struct EpidemicSystem;
+    pub struct AgeingSystem;
+    pub struct FiringSystem;
+    pub struct PromotionSystem;
+    pub struct HiringSystem;
+    pub struct FriendsSystem;
+    pub struct PartnersSystem;
+    pub struct ChildrenSystem;
+    pub struct MigrationSystem;
+    pub struct HouseholdsSystem;
+    pub struct GovernmentSystem;
+    pub struct HousesSystem;
+    pub struct CleanupSystem;
+
+    impl SimulationSystem for HealthSystem {
+        fn name(self: &Self) -> &str {
+            return "health";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, rng: &mut StdRng) {
+            city.update_health(rng);
+        }
+        fn run_before(self: &Self) -> Vec<String> {
+            return vec![String::from("ageing")];
+        }
+    }
+
+    impl SimulationSystem for EpidemicSystem {
+        fn name(self: &Self) -> &str {
+            return "epidemics";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, rng: &mut StdRng) {
+            city.spread_epidemics(rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("health")];
+        }
+        fn run_before(self: &Self) -> Vec<String> {
+            return vec![String::from("ageing")];
+        }
+    }
+
+    impl SimulationSystem for AgeingSystem {
+        fn name(self: &Self) -> &str {
+            return "ageing";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, rng: &mut StdRng) {
+            city.increment_citizen_ages(rng);
+        }
+    }
+
+    impl SimulationSystem for FiringSystem {
+        fn name(self: &Self) -> &str {
+            return "firing";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, rng: &mut StdRng) {
+            city.fire_percentage(city.config.employment.annual_firing_rate, rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("ageing")];
+        }
+    }
+
+    impl SimulationSystem for PromotionSystem {
+        fn name(self: &Self) -> &str {
+            return "promotions";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, rng: &mut StdRng) {
+            city.promote_staff(rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("firing")];
+        }
+        fn run_before(self: &Self) -> Vec<String> {
+            return vec![String::from("hiring")];
+        }
+    }
+
+    impl SimulationSystem for HiringSystem {
+        fn name(self: &Self) -> &str {
+            return "hiring";
+        }
+        fn run(self: &Self, city: &mut City, dict: &Dictionary, rng: &mut StdRng) {
+            city.fill_and_create_jobs(dict, rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("firing")];
+        }
+    }
+
+    impl SimulationSystem for FriendsSystem {
+        fn name(self: &Self) -> &str {
+            return "friends";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, rng: &mut StdRng) {
+            city.temp_add_friends(rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("ageing")];
+        }
+    }
+
+    impl SimulationSystem for PartnersSystem {
+        fn name(self: &Self) -> &str {
+            return "partners";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, rng: &mut StdRng) {
+            city.update_mind_partner_relations(rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("friends")];
+        }
+    }
+
+    impl SimulationSystem for ChildrenSystem {
+        fn name(self: &Self) -> &str {
+            return "children";
+        }
+        fn run(self: &Self, city: &mut City, dict: &Dictionary, rng: &mut StdRng) {
+            city.generate_children(dict, rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("partners")];
+        }
+    }
+
+    impl SimulationSystem for MigrationSystem {
+        fn name(self: &Self) -> &str {
+            return "migration";
+        }
+        fn run(self: &Self, city: &mut City, dict: &Dictionary, rng: &mut StdRng) {
+            city.migrate(dict, rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("children")];
+        }
+        fn run_before(self: &Self) -> Vec<String> {
+            return vec![String::from("households")];
+        }
+    }
+
+    impl SimulationSystem for HouseholdsSystem {
+        fn name(self: &Self) -> &str {
+            return "households";
+        }
+        fn run(self: &Self, city: &mut City, dict: &Dictionary, rng: &mut StdRng) {
+            city.update_households(dict, rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("children")];
+        }
+    }
+
+    impl SimulationSystem for GovernmentSystem {
+        fn name(self: &Self) -> &str {
+            return "government";
+        }
+        fn run(self: &Self, city: &mut City, dict: &Dictionary, rng: &mut StdRng) {
+            city.update_government(dict, rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("hiring"), String::from("migration")];
+        }
+    }
+
+    impl SimulationSystem for HousesSystem {
+        fn name(self: &Self) -> &str {
+            return "houses";
+        }
+        fn run(self: &Self, city: &mut City, dict: &Dictionary, rng: &mut StdRng) {
+            city.update_houses(dict, rng);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![String::from("children"), String::from("government")];
+        }
+    }
+
+    impl SimulationSystem for CleanupSystem {
+        fn name(self: &Self) -> &str {
+            return "cleanup";
+        }
+        fn run(self: &Self, city: &mut City, _dict: &Dictionary, _rng: &mut StdRng) {
+            city.cleanup(5);
+        }
+        fn run_after(self: &Self) -> Vec<String> {
+            return vec![
+                String::from("children"),
+                String::from("hiring"),
+                String::from("households"),
+            ];
+        }
+    }
+
+    pub fn default_systems() -> SystemRegistry {
+        let mut registry = SystemRegistry::new();
+        registry.register(Arc::new(HealthSystem));
+        registry.register(Arc::new(EpidemicSystem));
+        registry.register(Arc::new(AgeingSystem));
+        registry.register(Arc::new(FiringSystem));
+        registry.register(Arc::new(PromotionSystem));
+        registry.register(Arc::new(HiringSystem));
+        registry.register(Arc::new(FriendsSystem));
+        registry.register(Arc::new(PartnersSystem));
+        registry.register(Arc::new(ChildrenSystem));
+        registry.register(Arc::new(MigrationSystem));
+        registry.register(Arc::new(HouseholdsSystem));
+        registry.register(Arc::new(GovernmentSystem));
+        registry.register(Arc::new(HousesSystem));
+        registry.register(Arc::new(CleanupSystem));
+        return registry;
+    }
+
+    #[test]
+    fn test_system_ordering() {
+        struct Census;
+        impl SimulationSystem for Census {
+            fn name(self: &Self) -> &str {
+                return "census";
+            }
+            fn run(self: &Self, _city: &mut City, _dict: &Dictionary, _rng: &mut StdRng) {}
+            fn run_before(self: &Self) -> Vec<String> {
+                return vec![String::from("cleanup")];
+            }
+        }
+        let mut registry = default_systems();
+        registry.register(Arc::new(Census));
+        registry.set_enabled("firing", false);
+        registry.set_enabled("hiring", false);
+        registry.move_to("friends", 0);
+        let order: Vec<String> = registry
+            .ordered()
+            .iter()
+            .map(|s| s.name().to_string())
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "health",
+                "epidemics",
+                "ageing",
+                "friends",
+                "promotions",
+                "partners",
+                "children",
+                "migration",
+                "households",
+                "government",
+                "houses",
+                "census",
+                "cleanup"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_saved_systems_gain_new_defaults() {
+        let saved = vec![
+            SavedSystem {
+                name: String::from("ageing"),
+                enabled: true,
+            },
+            SavedSystem {
+                name: String::from("hiring"),
+                enabled: false,
+            },
+            SavedSystem {
+                name: String::from("friends"),
+                enabled: true,
+            },
+            SavedSystem {
+                name: String::from("cleanup"),
+                enabled: true,
+            },
+        ];
+        let registry = SystemRegistry::from(saved);
+        let mut expected = default_systems();
+        expected.set_enabled("hiring", false);
+        assert_eq!(registry, expected);
+    }
+}
@@ -0,0 +1,364 @@
+pub mod cli {
+    use std::{fs, path::Path};
+
+    use clap::{Parser, Subcommand, ValueEnum};
+    use procgen_templater::dictionary::dictionary::{build_dictionary_from_folder, Dictionary};
+
+    use crate::{
+        city::{
+            city::{random_city, City, Era},
+            config::config::{load_simulation_config, SimulationConfig},
+        },
+        seeded::templates::templates::{build_templates_from_folder, Templates},
+    };
+
+    #[derive(Parser, Debug)]
+    #[command(about = "Generate and simulate procedural cities")]
+    pub struct Cli {
+        #[command(subcommand)]
+        pub command: Command,
+    }
+
+    #[derive(Subcommand, Debug)]
+    pub enum Command {
+        /// Found a new city, simulate it and save a snapshot
+        Generate {
+            #[arg(long, value_enum, default_value_t = EraOption::Medieval)]
+            era: EraOption,
+            #[arg(long, default_value_t = 150)]
+            population: usize,
+            #[arg(long, default_value_t = 200)]
+            years: usize,
+            /// Seed for the city, picked at random when left out
+            #[arg(long)]
+            seed: Option<u64>,
+            #[arg(long, default_value = "./data_files")]
+            data_dir: String,
+            /// TOML file of simulation parameters, defaults are used when left out
+            #[arg(long)]
+            config: Option<String>,
+            /// Simulation systems to switch off, e.g. firing,hiring
+            #[arg(long, value_delimiter = ',')]
+            disable: Vec<String>,
+            /// Where to write the city snapshot
+            #[arg(long, default_value = "./export/city.json")]
+            snapshot: String,
+        },
+        /// Resume a saved city and simulate it for more years
+        Simulate {
+            #[arg(long, default_value = "./export/city.json")]
+            snapshot: String,
+            #[arg(long)]
+            years: usize,
+            #[arg(long, default_value = "./data_files")]
+            data_dir: String,
+            /// Where to write the updated snapshot, overwrites the input when left out
+            #[arg(long)]
+            output: Option<String>,
+            /// TOML file of simulation parameters to replace the ones saved with the city
+            #[arg(long)]
+            config: Option<String>,
+            /// Simulation systems to switch off, e.g. firing,hiring
+            #[arg(long, value_delimiter = ',')]
+            disable: Vec<String>,
+        },
+        /// Write a saved city out in one or more formats
+        Export {
+            #[arg(long, default_value = "./export/city.json")]
+            snapshot: String,
+            #[arg(long, value_enum, value_delimiter = ',', default_value = "markdown")]
+            formats: Vec<ExportFormat>,
+            #[arg(long, default_value = "./export")]
+            output_dir: String,
+            #[arg(long, default_value = "./data_files")]
+            data_dir: String,
+        },
+        /// Print the citizens, institutions and districts matching a name
+        Inspect {
+            name: String,
+            #[arg(long, default_value = "./export/city.json")]
+            snapshot: String,
+            #[arg(long, default_value = "./data_files")]
+            data_dir: String,
+        },
+        /// Found a city without saving it and report how long each simulation system takes
+        Bench {
+            #[arg(long, value_enum, default_value_t = EraOption::Medieval)]
+            era: EraOption,
+            #[arg(long, default_value_t = 10000)]
+            population: usize,
+            #[arg(long, default_value_t = 300)]
+            years: usize,
+            #[arg(long, default_value_t = 0)]
+            seed: u64,
+            #[arg(long, default_value = "./data_files")]
+            data_dir: String,
+            /// TOML file of simulation parameters, defaults are used when left out
+            #[arg(long)]
+            config: Option<String>,
+        },
+        /// Print what happened in the city, for one year or its whole history
+        History {
+            #[arg(long)]
+            year: Option<usize>,
+            #[arg(long, default_value = "./export/city.json")]
+            snapshot: String,
+            #[arg(long, default_value = "./data_files")]
+            data_dir: String,
+        },
+    }
+
+    #[derive(ValueEnum, Clone, Debug, PartialEq)]
+    pub enum EraOption {
+        Modern,
+        Medieval,
+        Fantasy,
+    }
+
+    impl EraOption {
+        pub fn to_era(self: &Self) -> Era {
+            return match self {
+                EraOption::Modern => Era::Modern,
+                EraOption::Medieval => Era::Medieval,
+                EraOption::Fantasy => Era::Fantasy,
+            };
+        }
+    }
+
+    #[derive(ValueEnum, Clone, Debug, PartialEq)]
+    pub enum ExportFormat {
+        Markdown,
+        Json,
+        Svg,
+    }
+
+    fn load_data(data_dir: &str) -> Result<(Dictionary, Templates), String> {
+        if !Path::new(data_dir).is_dir() {
+            return Err(format!("Dictionary folder {} does not exist", data_dir));
+        }
+        return Ok((
+            build_dictionary_from_folder(data_dir),
+            build_templates_from_folder(data_dir),
+        ));
+    }
+
+    fn check_parent_folder(path: &str) -> Result<(), String> {
+        let parent = Path::new(path).parent();
+        if parent.is_some() && !parent.unwrap().as_os_str().is_empty() && !parent.unwrap().is_dir()
+        {
+            return Err(format!(
+                "Output folder {} does not exist",
+                parent.unwrap().display()
+            ));
+        }
+        return Ok(());
+    }
+
+    fn disable_systems(city: &mut City, disable: Vec<String>) -> Result<(), String> {
+        let names = city.systems.names();
+        for name in disable {
+            if !names.contains(&name) {
+                return Err(format!(
+                    "Unknown simulation system {}, expected one of {}",
+                    name,
+                    names.join(", ")
+                ));
+            }
+            city.systems.set_enabled(&name, false);
+        }
+        return Ok(());
+    }
+
+    fn simulate_years(city: &mut City, dict: &Dictionary, years: usize) {
+        for _i in 0..years {
+            city.simulate_year(dict);
+            println!("Year {} ----------", city.year);
+            city.inspect_population();
+        }
+        city.cleanup(1);
+    }
+
+    pub fn run(cli: Cli) -> Result<(), String> {
+        match cli.command {
+            Command::Generate {
+                era,
+                population,
+                years,
+                seed,
+                data_dir,
+                config,
+                disable,
+                snapshot,
+            } => {
+                check_parent_folder(&snapshot)?;
+                let config = if config.is_some() {
+                    load_simulation_config(&config.unwrap())?
+                } else {
+                    SimulationConfig::default()
+                };
+                let (dict, templates) = load_data(&data_dir)?;
+                let seed = seed.unwrap_or_else(|| rand::random::<u64>());
+                println!("Seed: {}", seed);
+                let mut city =
+                    random_city(&dict, &templates, &config, era.to_era(), population, seed);
+                println!("{}: {}", city.name, city.site.describe());
+                disable_systems(&mut city, disable)?;
+                simulate_years(&mut city, &dict, years);
+                city.save(&snapshot)?;
+            }
+            Command::Simulate {
+                snapshot,
+                years,
+                data_dir,
+                output,
+                config,
+                disable,
+            } => {
+                let output = output.unwrap_or(snapshot.clone());
+                check_parent_folder(&output)?;
+                let (dict, templates) = load_data(&data_dir)?;
+                let mut city = City::load(&snapshot, &dict, &templates)?;
+                if config.is_some() {
+                    city.config = load_simulation_config(&config.unwrap())?;
+                }
+                disable_systems(&mut city, disable)?;
+                simulate_years(&mut city, &dict, years);
+                city.save(&output)?;
+            }
+            Command::Export {
+                snapshot,
+                formats,
+                output_dir,
+                data_dir,
+            } => {
+                if !Path::new(&output_dir).is_dir() {
+                    return Err(format!("Output folder {} does not exist", output_dir));
+                }
+                let (dict, templates) = load_data(&data_dir)?;
+                let city = City::load(&snapshot, &dict, &templates)?;
+                for format in formats {
+                    match format {
+                        ExportFormat::Markdown => city.export(&output_dir)?,
+                        ExportFormat::Json => city.save(
+                            Path::new(&output_dir)
+                                .join("city_export.json")
+                                .to_str()
+                                .unwrap(),
+                        )?,
+                        ExportFormat::Svg => {
+                            let path = Path::new(&output_dir).join("city_map.svg");
+                            fs::write(&path, city.export_map(&dict))
+                                .map_err(|e| format!("Could not write {}: {}", path.display(), e))?
+                        }
+                    }
+                }
+            }
+            Command::Inspect {
+                name,
+                snapshot,
+                data_dir,
+            } => {
+                let (dict, templates) = load_data(&data_dir)?;
+                let city = City::load(&snapshot, &dict, &templates)?;
+                println!("{}, year {}", city.name, city.year);
+                let target = name.to_ascii_lowercase();
+                let mut found = false;
+                for mind in city.population.values() {
+                    let full_name = format!("{} {}", mind.first_name, mind.last_name);
+                    if full_name.to_ascii_lowercase().eq(&target)
+                        || mind.first_name.to_ascii_lowercase().eq(&target)
+                    {
+                        mind.inspect(&city);
+                        println!("\n{}", mind.biography(&city));
+                        found = true;
+                    }
+                }
+                for institution in city.institutions.values() {
+                    if institution.name.to_ascii_lowercase().eq(&target) {
+                        institution.inspect(&city);
+                        found = true;
+                    }
+                }
+                for area in city.areas.values() {
+                    if area.name.to_ascii_lowercase().eq(&target) {
+                        print!("{}", area.print(&city));
+                        found = true;
+                    }
+                }
+                if !found {
+                    return Err(format!(
+                        "No citizen, institution or district of {} is named \"{}\"",
+                        city.name, name
+                    ));
+                }
+            }
+            Command::Bench {
+                era,
+                population,
+                years,
+                seed,
+                data_dir,
+                config,
+            } => {
+                let config = if config.is_some() {
+                    load_simulation_config(&config.unwrap())?
+                } else {
+                    SimulationConfig::default()
+                };
+                let (dict, templates) = load_data(&data_dir)?;
+                let mut city =
+                    random_city(&dict, &templates, &config, era.to_era(), population, seed);
+                city.benchmark(&dict, years).print();
+            }
+            Command::History {
+                year,
+                snapshot,
+                data_dir,
+            } => {
+                let (dict, templates) = load_data(&data_dir)?;
+                let city = City::load(&snapshot, &dict, &templates)?;
+                if year.is_some() {
+                    if year.unwrap() > city.year {
+                        return Err(format!(
+                            "Year {} has not happened yet, the city is in year {}",
+                            year.unwrap(),
+                            city.year
+                        ));
+                    }
+                    print!("{}", city.describe_year(year.unwrap()));
+                } else {
+                    print!("{}", city.export_chronicle());
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    #[test]
+    fn test_missing_folders_are_reported() {
+        let missing_data = run(Cli::parse_from([
+            "city_generator_2",
+            "generate",
+            "--data-dir",
+            "./no_such_data_folder",
+        ]));
+        assert_eq!(
+            missing_data,
+            Err(String::from(
+                "Dictionary folder ./no_such_data_folder does not exist"
+            ))
+        );
+        let missing_output = run(Cli::parse_from([
+            "city_generator_2",
+            "export",
+            "--output-dir",
+            "./no_such_export_folder",
+        ]));
+        assert_eq!(
+            missing_output,
+            Err(String::from(
+                "Output folder ./no_such_export_folder does not exist"
+            ))
+        );
+    }
+}
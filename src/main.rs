@@ -1,18 +1,14 @@
-use city::city::{random_city, Era};
-use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+use clap::Parser;
+use cli::cli::{run, Cli};
 pub mod city;
+pub mod cli;
 pub mod grammar;
+pub mod seeded;
 
 fn main() {
-    let dict = build_dictionary_from_folder("./data_files");
-
-    let mut city = random_city(&dict, Era::Medieval, 150);
-    for i in 0..200 {
-        println!("Year {} ----------", i);
-        city.inspect_population();
-        city.simulate_year(&dict);
-        city.print_debug_timer();
+    let result = run(Cli::parse());
+    if result.is_err() {
+        eprintln!("Error: {}", result.unwrap_err());
+        std::process::exit(1);
     }
-    city.cleanup(1);
-    city.export();
 }
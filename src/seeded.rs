@@ -0,0 +1,149 @@
+pub mod templates;
+pub mod seeded {
+    // procgen_templater draws from rand::thread_rng internally, so every draw that has to
+    // be reproducible from a city seed goes through here instead.
+    use std::collections::HashSet;
+
+    use procgen_templater::dictionary::{
+        dictionary::Dictionary,
+        word::word::{Word, WordType},
+    };
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+    use uuid::{Builder, Uuid};
+
+    // FNV-1a, used so stream names hash the same way on every run and platform
+    pub fn stream_hash(name: &str) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in name.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        return hash;
+    }
+
+    pub fn seeded_rng(seed: u64, stream: &str, year: usize) -> StdRng {
+        let year_offset = (year as u64).wrapping_mul(0x9E3779B97F4A7C15);
+        return StdRng::seed_from_u64(seed ^ stream_hash(stream) ^ year_offset);
+    }
+
+    // a mind's own stream within a phase, so its draws don't depend on which minds were
+    // handled before it or on which thread handles it
+    pub fn seeded_mind_rng(phase_seed: u64, id: &Uuid) -> StdRng {
+        let (high, low) = id.as_u64_pair();
+        return StdRng::seed_from_u64(phase_seed ^ high ^ low.rotate_left(32));
+    }
+
+    pub fn random_id(rng: &mut StdRng) -> Uuid {
+        return Builder::from_random_bytes(rng.gen()).into_uuid();
+    }
+
+    // an id that follows from content alone, for things made outside any seeded stream
+    pub fn content_id(content: &str) -> Uuid {
+        return random_id(&mut StdRng::seed_from_u64(stream_hash(content)));
+    }
+
+    // words ordered by base, then by their tags for words that share a base
+    fn word_order(word: &Word) -> (String, Vec<String>) {
+        let mut tags: Vec<String> = word.tags.iter().cloned().collect();
+        tags.sort();
+        return (word.base.clone(), tags);
+    }
+
+    pub fn random_word<'a>(
+        dict: &'a Dictionary,
+        query: (WordType, Vec<Vec<String>>),
+        rng: &mut StdRng,
+    ) -> Option<&'a Word> {
+        return random_word_without(dict, query, Vec::new(), rng);
+    }
+
+    pub fn random_word_without<'a>(
+        dict: &'a Dictionary,
+        query: (WordType, Vec<Vec<String>>),
+        without: Vec<String>,
+        rng: &mut StdRng,
+    ) -> Option<&'a Word> {
+        let (word_type, tag_groups) = query;
+        let mut groups: Vec<Vec<&HashSet<Uuid>>> = tag_groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .filter_map(|tag| {
+                        dict.index
+                            .tag_words
+                            .get(&(word_type.clone(), tag.trim().to_string()))
+                    })
+                    .collect()
+            })
+            .collect();
+        // broad tags like the era cover most of the dictionary, so start from the narrowest
+        // group and only check membership in the others
+        groups.sort_by_key(|sets| sets.iter().map(|ids| ids.len()).sum::<usize>());
+        let candidate_ids: Option<HashSet<Uuid>> = groups.split_first().map(|(first, rest)| {
+            return first
+                .iter()
+                .flat_map(|ids| ids.iter())
+                .filter(|id| {
+                    rest.iter()
+                        .all(|sets| sets.iter().any(|ids| ids.contains(id)))
+                })
+                .cloned()
+                .collect();
+        });
+        let mut candidates: Vec<&Word> = candidate_ids
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|id| dict.words.get(id))
+            .filter(|w| !without.iter().any(|t| w.tags.contains(t)))
+            .collect();
+        // word ids are regenerated every time a dictionary is built, so order by content
+        candidates.sort_by_cached_key(|w| word_order(w));
+        return candidates.choose(rng).copied();
+    }
+
+    #[test]
+    fn test_seeded_words() {
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let draw = |seed: u64| {
+            let mut rng = seeded_rng(seed, "test", 0);
+            return (0..20)
+                .map(|_| {
+                    random_word(
+                        &dict,
+                        (WordType::Noun, vec![vec!["FirstName".to_string()]]),
+                        &mut rng,
+                    )
+                    .unwrap()
+                    .base
+                    .clone()
+                })
+                .collect::<Vec<String>>();
+        };
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+
+        // words sharing a base are told apart by their tags, however a dictionary was built
+        let rebuilt = build_dictionary_from_folder("./data_files");
+        let tagged = |dict: &Dictionary| {
+            let mut rng = seeded_rng(7, "test", 0);
+            return (0..200)
+                .map(|_| {
+                    word_order(
+                        random_word(
+                            dict,
+                            (
+                                WordType::Noun,
+                                vec![vec!["FirstName".to_string(), "LastName".to_string()]],
+                            ),
+                            &mut rng,
+                        )
+                        .unwrap(),
+                    )
+                })
+                .collect::<Vec<(String, Vec<String>)>>();
+        };
+        assert_eq!(tagged(&dict), tagged(&rebuilt));
+    }
+}
@@ -0,0 +1,194 @@
+pub mod templates {
+    // Mirrors the TEMPLATE(...) entries of the dictionary data files so they can be
+    // picked and rendered from a seeded rng rather than procgen_templater's own.
+    use std::{
+        collections::{BTreeSet, HashMap},
+        fs,
+        path::Path,
+    };
+
+    use procgen_templater::dictionary::{dictionary::Dictionary, word::word::WordType};
+    use rand::{rngs::StdRng, seq::SliceRandom};
+    use regex::Regex;
+    use serde::{Deserialize, Serialize};
+
+    use crate::seeded::seeded::random_word;
+
+    #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+    pub struct Template {
+        pub body: String,
+        pub tags: BTreeSet<String>,
+    }
+
+    #[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+    pub struct Templates {
+        pub templates: Vec<Template>,
+    }
+
+    impl Templates {
+        pub fn get_random_template(
+            self: &Self,
+            tags: Vec<Vec<String>>,
+            rng: &mut StdRng,
+        ) -> Option<&Template> {
+            let candidates: Vec<&Template> = self
+                .templates
+                .iter()
+                .filter(|t| {
+                    tags.iter()
+                        .all(|group| group.iter().any(|tag| t.tags.contains(tag.trim())))
+                })
+                .collect();
+            return candidates.choose(rng).copied();
+        }
+
+        pub fn has_template(self: &Self, tags: Vec<Vec<String>>) -> bool {
+            return self.templates.iter().any(|t| {
+                tags.iter()
+                    .all(|group| group.iter().any(|tag| t.tags.contains(tag.trim())))
+            });
+        }
+
+        // as get_random_template, skipping any template carrying one of the without tags
+        pub fn get_random_template_without(
+            self: &Self,
+            tags: Vec<Vec<String>>,
+            without: Vec<String>,
+            rng: &mut StdRng,
+        ) -> Option<&Template> {
+            let candidates: Vec<&Template> = self
+                .templates
+                .iter()
+                .filter(|t| {
+                    tags.iter()
+                        .all(|group| group.iter().any(|tag| t.tags.contains(tag.trim())))
+                        && !without.iter().any(|tag| t.tags.contains(tag.trim()))
+                })
+                .collect();
+            return candidates.choose(rng).copied();
+        }
+    }
+
+    impl Template {
+        pub fn render(self: &Self, dict: &Dictionary, rng: &mut StdRng) -> Option<String> {
+            let token_pattern =
+                Regex::new(r"(NOUN|ADJECTIVE)\[((?:[\s,]*\[[^\[\]]*\])+)\]").unwrap();
+            let group_pattern = Regex::new(r"\[([^\[\]]*)\]").unwrap();
+            let mut output = String::new();
+            let mut last_end = 0;
+            for token in token_pattern.captures_iter(&self.body) {
+                let whole = token.get(0).unwrap();
+                let word_type = if token[1].eq("NOUN") {
+                    WordType::Noun
+                } else {
+                    WordType::Adjective
+                };
+                let tag_groups: Vec<Vec<String>> = group_pattern
+                    .captures_iter(&token[2])
+                    .map(|g| g[1].split(',').map(|t| t.trim().to_string()).collect())
+                    .collect();
+                let word = random_word(dict, (word_type, tag_groups), rng)?;
+                output.push_str(&self.body[last_end..whole.start()]);
+                output.push_str(&word.base);
+                last_end = whole.end();
+            }
+            output.push_str(&self.body[last_end..]);
+            return Some(output);
+        }
+
+        pub fn render_as_title(self: &Self, dict: &Dictionary, rng: &mut StdRng) -> Option<String> {
+            let rendered = self.render(dict, rng)?;
+            let words: Vec<String> = rendered
+                .split(' ')
+                .map(|w| {
+                    let mut chars = w.chars();
+                    return match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => String::new(),
+                    };
+                })
+                .collect();
+            return Some(words.join(" "));
+        }
+    }
+
+    fn expand_tag(tag: &str, parents: &HashMap<String, Vec<String>>, tags: &mut BTreeSet<String>) {
+        if tags.insert(tag.to_string()) {
+            for parent in parents.get(tag).unwrap_or(&Vec::new()) {
+                expand_tag(parent, parents, tags);
+            }
+        }
+    }
+
+    pub fn build_templates_from_folder(path: &str) -> Templates {
+        let tag_pattern = Regex::new(r"(?i)\bTAG\(([^)]*)\)").unwrap();
+        let parent_pattern = Regex::new(r"(?i)HAS_PARENT\(([^)]*)\)").unwrap();
+        let template_pattern = Regex::new(r"^TEMPLATE\(([^)]*)\)").unwrap();
+
+        let mut files: Vec<_> = fs::read_dir(Path::new(path))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p.extension().map(|e| e.eq("csv")).unwrap_or(false))
+            .collect();
+        files.sort();
+        let lines: Vec<String> = files
+            .iter()
+            .map(|p| fs::read_to_string(p).unwrap())
+            .flat_map(|contents| {
+                contents
+                    .lines()
+                    .map(|l| l.trim().to_string())
+                    .collect::<Vec<String>>()
+            })
+            .collect();
+
+        let mut parents: HashMap<String, Vec<String>> = HashMap::new();
+        for line in &lines {
+            if line.to_uppercase().starts_with("TAG(") {
+                let tag = tag_pattern.captures(line).unwrap()[1].trim().to_string();
+                for parent in parent_pattern.captures_iter(line) {
+                    parents
+                        .entry(tag.clone())
+                        .or_default()
+                        .push(parent[1].trim().to_string());
+                }
+            }
+        }
+
+        let mut templates: Vec<Template> = Vec::new();
+        for line in &lines {
+            let body = template_pattern.captures(line);
+            if body.is_some() {
+                let mut tags: BTreeSet<String> = BTreeSet::new();
+                for tag in tag_pattern.captures_iter(line) {
+                    expand_tag(tag[1].trim(), &parents, &mut tags);
+                }
+                templates.push(Template {
+                    body: body.unwrap()[1].to_string(),
+                    tags,
+                });
+            }
+        }
+        return Templates { templates };
+    }
+
+    #[test]
+    fn test_template_rendering() {
+        use crate::seeded::seeded::seeded_rng;
+        use procgen_templater::dictionary::dictionary::build_dictionary_from_folder;
+        let dict = build_dictionary_from_folder("./data_files");
+        let templates = build_templates_from_folder("./data_files");
+        let mut rng = seeded_rng(0, "test", 0);
+        for _i in 0..25 {
+            let template = templates
+                .get_random_template(
+                    vec![vec!["Social".to_string()], vec!["EraMedieval".to_string()]],
+                    &mut rng,
+                )
+                .unwrap();
+            let name = template.render(&dict, &mut rng).unwrap();
+            assert!(!name.contains("NOUN") && !name.contains("ADJECTIVE"));
+            println!("{}", name);
+        }
+    }
+}